@@ -1,5 +1,7 @@
+use std::io::BufRead;
 use std::iter;
 
+#[derive(Clone, Copy)]
 pub enum TaskOfDay {
     First,
     Second,
@@ -34,3 +36,13 @@ pub fn separate_by_blanks(input: &Vec<String>, joiner: &str) -> Vec<String> {
 pub fn string_to_lines(s: &str) -> Vec<String> {
     s.split("\n").map(|s| s.trim().to_string()).collect()
 }
+
+/// Lazily yields the trimmed, non-blank lines of a reader, the file is not read at once.
+/// Day 1 part 1 keeps a fixed table of seen values. Solvers that need all values, e.g.,
+/// day 1 part 2, day 9 part 2 and day 10, still collect the parsed numbers.
+pub fn stream_lines<R: BufRead>(reader: R) -> impl Iterator<Item = String> {
+    reader
+        .lines()
+        .map(|line| line.expect("Could not read line.").trim().to_string())
+        .filter(|s| s.len() > 0)
+}
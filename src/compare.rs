@@ -0,0 +1,235 @@
+// Runs every solver on the inputs of several users, expected in
+//     <dir>/<user>/input_NN.txt
+// and prints a matrix of answers and timings with one column per user.
+use super::common::TaskOfDay;
+use super::perf::median;
+use super::solvers::{all_solvers, Solver};
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// a run is flagged as slow if it takes more than this factor times the median of all users
+const SLOW_FACTOR: u128 = 5;
+// runs faster than this are never flagged as slow
+const MIN_SLOW_MICROS: u128 = 10000;
+// solvers that do not terminate within this time, e.g., due to an endless loop, are abandoned
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Outcome {
+    Answer(String),
+    NoAnswer,
+    Panic(String),
+    Timeout,
+}
+
+#[derive(Clone, Debug)]
+struct Cell {
+    outcome: Outcome,
+    micros: u128,
+}
+
+struct Row {
+    day: i32,
+    part: usize,
+    // None if the user has no input for this day
+    cells: Vec<Option<Cell>>,
+}
+
+fn find_users(dir: &Path) -> Result<Vec<String>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Could not read '{}': {}", dir.display(), e))?;
+    let mut users = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect::<Vec<String>>();
+    users.sort();
+    Ok(users)
+}
+
+fn run_cell(solver: &Solver, content: &str, part: TaskOfDay, timeout: Duration) -> Cell {
+    let now = Instant::now();
+    let (sender, receiver) = mpsc::channel();
+    let (solver, content) = (*solver, content.to_string());
+    // a thread cannot be killed, in case of a timeout it keeps running until the process exits,
+    // which `compare` leaves to main
+    thread::spawn(move || {
+        let _ = sender.send(solver.try_solve_str(&content, part));
+    });
+    let outcome = match receiver.recv_timeout(timeout) {
+        Ok(Ok(Some(answer))) => Outcome::Answer(answer),
+        Ok(Ok(None)) => Outcome::NoAnswer,
+        Ok(Err(msg)) => Outcome::Panic(msg),
+        Err(_) => Outcome::Timeout,
+    };
+    Cell {
+        outcome: outcome,
+        micros: now.elapsed().as_micros(),
+    }
+}
+
+fn run_rows(dir: &Path, users: &[String], timeout: Duration) -> Vec<Row> {
+    let mut rows: Vec<Row> = Vec::with_capacity(0);
+    for solver in all_solvers() {
+        let contents = users
+            .iter()
+            .map(|user| {
+                let path = dir.join(user).join(format!("input_{:02}.txt", solver.day));
+                fs::read_to_string(path).ok()
+            })
+            .collect::<Vec<Option<String>>>();
+        if contents.iter().all(|c| c.is_none()) {
+            continue;
+        }
+        for (part_number, part) in [(1, TaskOfDay::First), (2, TaskOfDay::Second)].iter() {
+            let cells = contents
+                .iter()
+                .map(|content| Some(run_cell(&solver, content.as_ref()?, *part, timeout)))
+                .collect::<Vec<Option<Cell>>>();
+            rows.push(Row {
+                day: solver.day,
+                part: *part_number,
+                cells: cells,
+            });
+        }
+    }
+    rows
+}
+
+fn is_slow(cell: &Cell, row_median: u128) -> bool {
+    cell.micros > MIN_SLOW_MICROS && cell.micros > SLOW_FACTOR * row_median
+}
+
+/// Returns one line per cell that panicked, found no answer or was unusually slow.
+fn flags(rows: &[Row], users: &[String]) -> Vec<String> {
+    let mut res: Vec<String> = Vec::with_capacity(0);
+    for row in rows {
+        let row_median =
+            median(row.cells.iter().flatten().map(|c| c.micros).collect()).unwrap_or(0);
+        for (user, cell) in izip!(users.iter(), row.cells.iter()) {
+            let cell = match cell {
+                Some(c) => c,
+                None => continue,
+            };
+            let prefix = format!("day {:02} part {} on {}:", row.day, row.part, user);
+            match &cell.outcome {
+                Outcome::Panic(msg) => res.push(format!("{} PANIC {}", prefix, msg)),
+                Outcome::NoAnswer => res.push(format!("{} NO ANSWER", prefix)),
+                Outcome::Timeout => res.push(format!("{} TIMEOUT", prefix)),
+                Outcome::Answer(_) => (),
+            }
+            if is_slow(cell, row_median) {
+                res.push(format!(
+                    "{} SLOW {} millis, median {} millis",
+                    prefix,
+                    cell.micros / 1000,
+                    row_median / 1000
+                ));
+            }
+        }
+    }
+    res
+}
+
+fn format_cell(cell: &Option<Cell>) -> String {
+    match cell {
+        None => "-".to_string(),
+        Some(c) => {
+            let value = match &c.outcome {
+                Outcome::Answer(a) => a.clone(),
+                Outcome::NoAnswer => "NO ANSWER".to_string(),
+                Outcome::Panic(_) => "PANIC".to_string(),
+                Outcome::Timeout => "TIMEOUT".to_string(),
+            };
+            format!("{} ({} millis)", value, c.micros / 1000)
+        }
+    }
+}
+
+fn format_matrix(rows: &[Row], users: &[String]) -> String {
+    let header = std::iter::once("day/part".to_string()).chain(users.iter().cloned());
+    let lines = std::iter::once(header.collect::<Vec<String>>()).chain(rows.iter().map(|row| {
+        std::iter::once(format!("{:02}/{}", row.day, row.part))
+            .chain(row.cells.iter().map(format_cell))
+            .collect::<Vec<String>>()
+    }));
+    let lines = lines.collect::<Vec<Vec<String>>>();
+    let widths = (0..users.len() + 1)
+        .map(|i| lines.iter().map(|l| l[i].len()).max().unwrap_or(0))
+        .collect::<Vec<usize>>();
+    lines
+        .iter()
+        .map(|l| {
+            let padded = izip!(l.iter(), widths.iter())
+                .map(|(s, w)| format!("{:w$}", s, w = w))
+                .collect::<Vec<String>>();
+            format!("{}\n", padded.join("  ").trim_end())
+        })
+        .collect::<String>()
+}
+
+/// Prints the answer matrix and all flags and returns whether there were any flags. Solvers
+/// that timed out are still running, the caller is expected to exit the process.
+pub fn compare(args: &[String]) -> Result<bool, String> {
+    let dir = match args {
+        [dir] => Path::new(dir),
+        _ => return Err("Usage: compare <dir with one sub-directory of inputs per user>".to_string()),
+    };
+    let users = find_users(dir)?;
+    if users.len() == 0 {
+        return Err(format!("There are no user directories in '{}'.", dir.display()));
+    }
+    // panics are printed by the default hook as they happen and listed again in the flags
+    let rows = run_rows(dir, &users, TIMEOUT);
+
+    print!("{}", format_matrix(&rows, &users));
+    let flags = flags(&rows, &users);
+    if flags.len() > 0 {
+        println!();
+        for flag in &flags {
+            println!("{}", flag);
+        }
+    }
+    Ok(flags.len() > 0)
+}
+
+#[test]
+fn test_compare() {
+    let dir = std::env::temp_dir().join(format!("aoc2020_compare_{}", std::process::id()));
+    fs::create_dir_all(dir.join("alice")).unwrap();
+    fs::create_dir_all(dir.join("bob")).unwrap();
+    fs::write(dir.join("alice").join("input_01.txt"), "1721\n979\n366\n299\n675\n1456\n").unwrap();
+    fs::write(dir.join("bob").join("input_01.txt"), "1721\nx\n").unwrap();
+    fs::write(dir.join("bob").join("input_05.txt"), "BFFFBBFRRR\n").unwrap();
+
+    let users = find_users(&dir).unwrap();
+    assert_eq!(users, vec!["alice", "bob"]);
+    let rows = run_rows(&dir, &users, TIMEOUT);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(rows.len(), 4);
+    assert_eq!((rows[0].day, rows[0].part), (1, 1));
+    let alice = rows[0].cells[0].as_ref().unwrap();
+    assert_eq!(alice.outcome, Outcome::Answer("514579".to_string()));
+    let bob = rows[0].cells[1].as_ref().unwrap();
+    assert!(matches!(bob.outcome, Outcome::Panic(_)));
+    assert!(rows[2].cells[0].is_none());
+    assert_eq!(rows[2].cells[1].as_ref().unwrap().outcome, Outcome::Answer("567".to_string()));
+
+    // a single seat has no gap, hence day 5 part 2 panics as well
+    let flags = flags(&rows, &users);
+    assert_eq!(flags.len(), 3);
+    assert!(flags[0].starts_with("day 01 part 1 on bob: PANIC"));
+    assert!(flags[2].starts_with("day 05 part 2 on bob: PANIC"));
+    let matrix = format_matrix(&rows, &users);
+    assert!(matrix.starts_with("day/part"));
+    assert!(matrix.contains("05/1      -"));
+
+    let fast = Cell { outcome: Outcome::NoAnswer, micros: 20000 };
+    assert!(is_slow(&fast, 1000));
+    assert!(!is_slow(&fast, 5000));
+    let fast = Cell { outcome: Outcome::NoAnswer, micros: 5000 };
+    assert!(!is_slow(&fast, 10));
+}
@@ -1,20 +1,37 @@
 use super::common::TaskOfDay;
 
-pub fn run(input: &Vec<String>, part: TaskOfDay) -> i32 {
-    let converted = input
-        .iter()
-        .map(|s| s.parse::<i32>().expect("could not parse string to int {}"));
+fn parse(s: &str) -> i32 {
+    s.parse::<i32>().expect("could not parse string to int {}")
+}
+
+pub fn run_stream<I, S>(input: I, part: TaskOfDay) -> i32
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut converted = input.map(|s| parse(s.as_ref()));
     match part {
         TaskOfDay::First => {
-            let t = iproduct!(converted.clone(), converted.clone())
-                .find(|(i, j)| i + j == 2020)
+            // only values up to 2020 can be part of the sum, a fixed table of those we have
+            // seen is all we need to find the first match
+            let mut seen = [false; 2021];
+            let i = converted
+                .find(|&i| {
+                    if !(0..=2020).contains(&i) {
+                        return false;
+                    }
+                    let found = seen[(2020 - i) as usize];
+                    seen[i as usize] = true;
+                    found
+                })
                 .unwrap();
-            t.0 * t.1
+            i * (2020 - i)
         }
         TaskOfDay::Second => {
+            let converted = converted.collect::<Vec<i32>>();
             let t = iproduct!(
-                iproduct!(converted.clone(), converted.clone()).filter(|&(i, j)| i + j <= 2020),
-                converted
+                iproduct!(converted.iter(), converted.iter()).filter(|&(i, j)| i + j <= 2020),
+                converted.iter()
             )
             .find(|&(t, k)| t.0 + t.1 + k == 2020)
             .unwrap();
@@ -22,3 +39,26 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> i32 {
         }
     }
 }
+
+pub fn run(input: &Vec<String>, part: TaskOfDay) -> i32 {
+    run_stream(input.iter(), part)
+}
+
+#[test]
+fn test() {
+    use super::common::string_to_lines;
+
+    let input = string_to_lines(
+        "1721
+        979
+        366
+        299
+        675
+        1456",
+    );
+    assert_eq!(run(&input, TaskOfDay::First), 514579);
+    assert_eq!(run(&input, TaskOfDay::Second), 241861950);
+    assert_eq!(run_stream(input.into_iter(), TaskOfDay::First), 514579);
+    let large = ["3000", "1010", "-5", "1010"];
+    assert_eq!(run_stream(large.iter(), TaskOfDay::First), 1010 * 1010);
+}
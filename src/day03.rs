@@ -1,21 +1,20 @@
 use super::common::TaskOfDay;
+use super::grid::{BinaryCodec, Grid, WrapAxes};
 
-fn sum_of_steps(tree_matrix: &Vec<Vec<i32>>, row_step: usize, col_step: usize) -> i32 {
+fn sum_of_steps(tree_matrix: &Grid<i32>, row_step: usize, col_step: usize) -> i32 {
+    let wrapped = tree_matrix.wrapping_view(WrapAxes::Cols);
     let mut sum = 0i32;
     for (row, col) in izip!(
-        (row_step..tree_matrix.len()).step_by(row_step),
-        (col_step..tree_matrix.len() * col_step).step_by(col_step)
+        (row_step..tree_matrix.rows).step_by(row_step),
+        (col_step..tree_matrix.rows * col_step).step_by(col_step)
     ) {
-        sum += tree_matrix[row].iter().cycle().nth(col).unwrap();
+        sum += wrapped.at(row, col);
     }
     sum
 }
 
 pub fn run(input: &Vec<String>, part: TaskOfDay) -> i64 {
-    let tree_matrix = input
-        .iter()
-        .map(|s| s.chars().map(|c| (c == '#') as i32).collect::<Vec<i32>>())
-        .collect::<Vec<Vec<i32>>>();
+    let tree_matrix = Grid::from_lines_with(input, &BinaryCodec).unwrap();
     match part {
         TaskOfDay::First => sum_of_steps(&tree_matrix, 1, 3) as i64,
         TaskOfDay::Second => izip!(
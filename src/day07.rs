@@ -87,6 +87,8 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> i32 {
 
 #[test]
 fn test() {
+    use super::snapshot::assert_snapshot;
+
     let empty_hashmap: HashMap<&str, i32> = HashMap::with_capacity(0);
     let input_str = "light red bags contain 1 bright white bag, 2 muted yellow bags.
 dark orange bags contain 3 bright white bags, 4 muted yellow bags, 3 dotted black bags.
@@ -165,4 +167,23 @@ dark violet bags contain no other bags.";
     let input2: Vec<String> = input2_str.split("\n").map(|s| s.to_string()).collect();
 
     assert_eq!(run(&input2, TaskOfDay::Second), 126);
+
+    // hash maps have no stable order, hence we sort containers and contents
+    let bag_map_to_string = |bag_map: &HashMap<&str, HashMap<&str, i32>>| {
+        let mut containers = bag_map.keys().collect::<Vec<_>>();
+        containers.sort();
+        containers
+            .iter()
+            .map(|container| {
+                let mut content = bag_map[*container]
+                    .iter()
+                    .map(|(bag, num)| format!("{} {}", num, bag))
+                    .collect::<Vec<String>>();
+                content.sort();
+                format!("{}: {}\n", container, content.join(", "))
+            })
+            .collect::<String>()
+    };
+    assert_snapshot("day07_bag_map", &bag_map_to_string(&bag_map));
+    assert_snapshot("day07_bag_map_2", &bag_map_to_string(&merge_line_maps(&input2)));
 }
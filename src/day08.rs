@@ -2,6 +2,7 @@ use super::common::split_in2_tuple;
 use super::common::TaskOfDay;
 use std::collections::HashSet;
 
+#[derive(Debug)]
 enum Op {
     Jmp,
     Nop,
@@ -33,6 +34,7 @@ fn accumulator_at_loop_or_termination(operations: &Vec<(Op, i32)>) -> (i32, bool
     let mut idx = 0usize;
     while !set.contains(&idx) && idx < operations.len() {
         set.insert(idx);
+        trace!("vm ip {} acc {} op {:?} {}", idx, accumulator, operations[idx].0, operations[idx].1);
         match operations[idx].0 {
             Op::Acc => {
                 accumulator += operations[idx].1;
@@ -79,6 +81,7 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> i32 {
                 instructions[i].0 = swap(&instructions[i].0);
 
                 let (acc, looping) = accumulator_at_loop_or_termination(&instructions);
+                debug!("swapped op at {}, looping {}, acc {}", i, looping, acc);
                 if looping {
                     // undo swap and try next
                     instructions[i].0 = swap(&instructions[i].0);
@@ -1,4 +1,27 @@
 use super::common::TaskOfDay;
+use std::collections::VecDeque;
+
+fn is_sum_of_two(window: &VecDeque<usize>, value: usize) -> bool {
+    iproduct!(window.iter(), window.iter())
+        .filter(|(i, j)| i < j)
+        .any(|(i, j)| i + j == value)
+}
+
+/// Same as `find_invalid` but only keeps the preambel window in memory.
+fn find_invalid_in_stream<I: Iterator<Item = usize>>(
+    mut numbers: I,
+    preambel_len: usize,
+) -> Option<usize> {
+    let mut window: VecDeque<usize> = numbers.by_ref().take(preambel_len).collect();
+    for number in numbers {
+        if !is_sum_of_two(&window, number) {
+            return Some(number);
+        }
+        window.pop_front();
+        window.push_back(number);
+    }
+    None
+}
 
 fn find_invalid(input: &Vec<usize>, preambel_len: usize) -> usize {
     let idx = (preambel_len..input.len())
@@ -36,21 +59,28 @@ fn find_contiguous(input: &Vec<usize>, target: usize) -> Option<(usize, usize)>
     Some((start?, end?))
 }
 
-pub fn run(input: &Vec<String>, part: TaskOfDay) -> usize {
-    let input_usize = input
-        .iter()
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect::<Vec<usize>>();
-    let invalid_number = find_invalid(&input_usize, 25usize);
+pub fn run_stream<I, S>(input: I, part: TaskOfDay) -> usize
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    let numbers = input.map(|s| s.as_ref().parse::<usize>().unwrap());
     match part {
-        TaskOfDay::First => invalid_number,
+        TaskOfDay::First => find_invalid_in_stream(numbers, 25usize).unwrap(),
         TaskOfDay::Second => {
+            // the contiguous range can be anywhere, hence we need all numbers
+            let input_usize = numbers.collect::<Vec<usize>>();
+            let invalid_number = find_invalid(&input_usize, 25usize);
             let (start, end) = find_contiguous(&input_usize, invalid_number).unwrap();
             input_usize[start..end].iter().max().unwrap() + input_usize[start..end].iter().min().unwrap()
         },
     }
 }
 
+pub fn run(input: &Vec<String>, part: TaskOfDay) -> usize {
+    run_stream(input.iter(), part)
+}
+
 #[test]
 fn test() {
     let input = vec![
@@ -58,6 +88,8 @@ fn test() {
     ];
     let invalid_number = find_invalid(&input, 5);
     assert_eq!(invalid_number, 127);
+    assert_eq!(find_invalid_in_stream(input.iter().cloned(), 5), Some(127));
+    assert_eq!(find_invalid_in_stream(input[..14].iter().cloned(), 5), None);
     assert_eq!(find_contiguous_starting_at(&input, 35, 0).unwrap(), 1);
     assert_eq!(find_contiguous_starting_at(&input, 120, 7).unwrap(), 9);
     assert_eq!(find_contiguous_starting_at(&input, 112, 3).unwrap(), 6);
@@ -141,13 +141,14 @@ fn count_combinations(gaps: &Vec<usize>) -> usize {
         .product()
 }
 
-pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
-    let sort_input = || {
-        let mut sor_in: Vec<usize> = input.iter().map(|s| s.parse::<usize>().unwrap()).collect();
-        sor_in.sort();
-        sor_in
-    };
-    let sorted_input = sort_input();
+pub fn run_stream<I, S>(input: I, part: TaskOfDay) -> Option<usize>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    // sorting needs all adapters, but we only keep the parsed numbers and not the lines
+    let mut sorted_input: Vec<usize> = input.map(|s| s.as_ref().parse::<usize>().unwrap()).collect();
+    sorted_input.sort();
     let gaps = compute_gaps(&sorted_input)?;
     match part {
         TaskOfDay::First => {
@@ -158,6 +159,10 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
     }
 }
 
+pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
+    run_stream(input.iter(), part)
+}
+
 #[test]
 fn test() {
     let input: Vec<String> = "28
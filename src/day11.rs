@@ -1,149 +1,83 @@
 use super::common::TaskOfDay;
-use super::grid::Grid;
+use super::grid::{Automaton, FrameWriter, Gather, Grid, Neighborhood, MOORE_OFFSETS};
+use std::thread;
 
-#[derive(Clone)]
-struct Hood {
-    data: [u8; 8],
-    len: usize,
+fn visible_seats(grid: &Grid<u8>, row: usize, col: usize, seats: &mut Vec<u8>) {
+    let hits = grid.visible_from((row, col), &MOORE_OFFSETS, |v| *v > 0);
+    seats.extend(hits.map(|hit| *hit.value));
 }
 
-impl IntoIterator for Hood {
-    type Item = u8;
-    type IntoIter = HoodIterator;
-    fn into_iter(self) -> Self::IntoIter {
-        HoodIterator {
-            hood: self,
-            index: 0,
-        }
-    }
-}
-
-#[derive(Clone)]
-struct HoodIterator {
-    hood: Hood,
-    index: usize,
-}
-impl Iterator for HoodIterator {
-    type Item = u8;
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.hood.len {
-            self.index += 1;
-            return Some(self.hood.data[self.index - 1]);
-        } else {
-            None
-        }
-    }
-}
-
-impl Hood {
-    fn create(grid: &Grid<u8>, row: i32, col: i32) -> Hood {
-        let mut hood_len = 0;
-        let mut data = [0; 8];
-        for (r, c) in iproduct!([-1, 0, 1].iter(), [-1, 0, 1].iter()) {
-            let ri = row + r;
-            let ci = col + c;
-            if ri >= 0
-                && ci >= 0
-                && ri < grid.rows as i32
-                && ci < grid.cols as i32
-                && (ri, ci) != (row, col)
-            {
-                data[hood_len] = grid[ri as usize][ci as usize];
-                hood_len += 1;
-            }
-        }
-        Hood {
-            data: data,
-            len: hood_len,
-        }
-    }
-
-    fn create2(grid: &Grid<u8>, row: i32, col: i32) -> Hood {
-        let (rows, cols) = (grid.rows as i32, grid.cols as i32);
-        let mut hood_len = 0;
-        let mut data = [0; 8];
-        for (r, c) in iproduct!([-1, 0, 1].iter(), [-1, 0, 1].iter()) {
-            let mut scale: i32 = 1;
-            while (*r != 0 || *c != 0)
-                && row + (r * scale) >= 0
-                && col + (c * scale) >= 0
-                && row + (r * scale) < rows
-                && col + (c * scale) < cols
-                && grid[(row + (r * scale)) as usize][(col + (c * scale)) as usize] == 0
-            {
-                scale += 1;
-            }
-            let ri = row + r * scale;
-            let ci = col + c * scale;
-
-            if ri >= 0
-                && ci >= 0
-                && ri < rows
-                && ci < cols
-                && (ri, ci) != (row, col)
-                && grid[ri as usize][ci as usize] > 0
-            {
-                data[hood_len] = grid[ri as usize][ci as usize];
-                hood_len += 1;
-            }
-        }
-        Hood {
-            data: data,
-            len: hood_len,
-        }
-    }
-}
-
-fn simulation_step(
-    grid: &Grid<u8>,
-    hood_creator: fn(&Grid<u8>, i32, i32) -> Hood,
-    max_annoyance: usize,
-) -> Grid<u8> {
-    let mut new_grid = grid.clone();
-    for (r, c) in iproduct!(0..grid.rows as i32, 0..grid.cols as i32) {
-        let hood = hood_creator(&grid, r, c);
-        let hood_iter = hood.into_iter();
-        new_grid[r as usize][c as usize] = match grid[r as usize][c as usize] {
+fn seat_rule(max_annoyance: usize) -> impl Fn(u8, &[u8]) -> u8 {
+    move |seat, hood| {
+        let occupied = hood.iter().filter(|v| **v == 2).count();
+        match seat {
             0 => 0,
-            1 => match hood_iter.clone().filter(|v| *v == 2).count() {
+            1 => match occupied {
                 0 => 2,
                 _ => 1,
             },
             2 => {
-                if hood_iter.clone().filter(|v| *v == 2).count() < max_annoyance {
+                if occupied < max_annoyance {
                     2
                 } else {
                     1
                 }
             }
-            _ => panic!("Unknown grid value {}", grid[r as usize][c as usize]),
-        };
+            _ => panic!("Unknown grid value {}", seat),
+        }
     }
-    new_grid
+}
+
+
+fn seat_color(value: u8) -> [u8; 3] {
+    match value {
+        0 => [40, 40, 40],
+        1 => [60, 180, 75],
+        _ => [230, 25, 75],
+    }
+}
+
+/// Number of occupied seats once the seating is stable.
+fn occupied_when_stable<G: Gather<u8> + Sync>(
+    grid: Grid<u8>,
+    gather: G,
+    max_annoyance: usize,
+    prefix: &str,
+) -> usize {
+    let mut frames = FrameWriter::from_env(prefix, 4);
+    let mut write_frame = move |grid: &Grid<u8>| {
+        if let Some(writer) = frames.as_mut() {
+            if let Err(e) = writer.write_frame(grid, seat_color) {
+                error!("Could not write frame: {}", e);
+            }
+        }
+    };
+    write_frame(&grid);
+    let mut seats = Automaton::with_gather(grid, gather, seat_rule(max_annoyance));
+    seats.set_threads(thread::available_parallelism().map_or(1, |n| n.get()));
+    seats.on_generation(move |generation, grid| {
+        trace!("generation {}\n{:?}", generation, grid);
+        write_frame(grid);
+    });
+    let generation = seats.run_until_stable();
+    debug!("stable after {} generations", generation);
+    seats.grid().data.iter().filter(|v| *v == &2u8).count()
 }
 
 pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
     let grid = Grid::from_lines(input)?;
-    type HoodCreatorType = fn(&Grid<u8>, i32, i32) -> Hood;
-    let first_create: HoodCreatorType = Hood::create;
-    let second_create: HoodCreatorType = Hood::create2;
-    let (hood_creator, max_annoyance) = match part {
-        TaskOfDay::First => (first_create, 4),
-        TaskOfDay::Second => (second_create, 5),
-    };
-    let mut prev_grid = grid.clone();
-    let mut new_grid = simulation_step(&grid, hood_creator, max_annoyance);
-    while new_grid.data != prev_grid.data {
-        prev_grid = new_grid;
-        new_grid = simulation_step(&prev_grid, hood_creator, max_annoyance);
-    }
-    Some(new_grid.data.iter().filter(|v| *v == &2u8).count())
+    Some(match part {
+        TaskOfDay::First => occupied_when_stable(grid, Neighborhood::Moore, 4, "day11_first"),
+        TaskOfDay::Second => occupied_when_stable(grid, visible_seats, 5, "day11_second"),
+    })
 }
 
 #[test]
 fn test() {
     use std::collections::HashSet;
     use super::common::string_to_lines;
+    use super::snapshot::assert_snapshot;
+    use super::grid::side_by_side;
 
     let input = string_to_lines(
         "L.LL.LL.LL
@@ -159,18 +93,65 @@ fn test() {
     );
 
     let grid = Grid::from_lines(&input).unwrap();
+    let assert_seats_eq = |expected: &Grid<u8>, actual: &Grid<u8>| {
+        assert!(
+            expected == actual,
+            "expected | actual\n{}",
+            side_by_side(&expected.to_lines(), &actual.to_lines())
+        );
+    };
 
-    let hood = Hood::create(&grid, 9, 9);
-    assert_eq!(hood.len, 3);
-    assert!(hood.data.contains(&0u8) && hood.data.contains(&1u8));
-    assert_eq!(hood.clone().into_iter().collect::<HashSet<u8>>().len(), 2);
-    assert_eq!(hood.into_iter().collect::<Vec<u8>>().len(), 3);
+    let gathered = |gather: &dyn Gather<u8>, lines: &[&str], row, col| {
+        let grid = Grid::from_lines(&string_to_lines(&lines.join("\n"))).unwrap();
+        let mut seats = vec![];
+        gather.gather(&grid, row, col, &mut seats);
+        seats
+    };
+    // part 1 looks at the adjacent cells only, floor included
+    let moore = &Neighborhood::Moore;
+    assert_eq!(gathered(moore, &["L.L", "LL#"], 0, 0), vec![0, 1, 1]);
+    assert_eq!(
+        gathered(moore, &["L.LL", "LLLL", "L.L."], 1, 1),
+        vec![1, 0, 1, 1, 1, 1, 0, 1]
+    );
+    // part 2 sees the first seat in each direction, examples from the puzzle
+    let eight_occupied = [
+        ".......#.",
+        "...#.....",
+        ".#.......",
+        ".........",
+        "..#L....#",
+        "....#....",
+        ".........",
+        "#........",
+        "...#.....",
+    ];
+    assert_eq!(gathered(&visible_seats, &eight_occupied, 4, 3), vec![2; 8]);
+    let one_empty = [".............", ".L.L.#.#.#.#.", "............."];
+    assert_eq!(gathered(&visible_seats, &one_empty, 1, 1), vec![1]);
+    let none_visible = [
+        ".##.##.", "#.#.#.#", "##...##", "...L...", "##...##", "#.#.#.#", ".##.##.",
+    ];
+    assert_eq!(gathered(&visible_seats, &none_visible, 3, 3), vec![]);
+
+    let mut seats = vec![];
+    visible_seats(&grid, 9, 9, &mut seats);
+    assert_eq!(seats, vec![1, 1, 1]);
+    seats.clear();
+    visible_seats(&grid, 4, 4, &mut seats);
+    assert_eq!(seats.len(), 8);
+    assert_eq!(seats.iter().collect::<HashSet<&u8>>().len(), 1);
 
-    let hood = Hood::create(&grid, 4, 4);
-    assert_eq!(hood.len, 8);
-    assert!(hood.data.contains(&0u8) && hood.data.contains(&1u8));
-    assert_eq!(hood.clone().into_iter().collect::<HashSet<u8>>().len(), 2);
-    assert_eq!(hood.into_iter().collect::<Vec<u8>>().len(), 8);
+    let (first, second) = (seat_rule(4), seat_rule(5));
+    // floor never changes, an empty seat is taken if no occupied seat is around
+    assert_eq!(first(0, &[2; 8]), 0);
+    assert_eq!(first(1, &[0, 1, 1]), 2);
+    assert_eq!(first(1, &[1, 2, 1]), 1);
+    // occupied seats are left if there are 4 occupied seats around in part 1 but 5 in part 2
+    assert_eq!(first(2, &[2, 2, 2, 1, 0]), 2);
+    assert_eq!(first(2, &[2, 2, 2, 2, 1]), 1);
+    assert_eq!(second(2, &[2, 2, 2, 2, 1]), 2);
+    assert_eq!(second(2, &[2; 5]), 1);
 
     assert_eq!(grid[0][0], 1);
     assert_eq!(grid[0][1], 0);
@@ -178,7 +159,8 @@ fn test() {
     assert_eq!(grid[3][4], 0);
     assert_eq!(run(&input, TaskOfDay::First).unwrap(), 37);
 
-    let grid_after_1 = simulation_step(&grid, Hood::create2, 5);
+    let mut seats = Automaton::with_gather(grid.clone(), visible_seats, seat_rule(5));
+    assert!(seats.step());
     let after_1_str = string_to_lines(
         "#.##.##.##
          #######.##
@@ -192,7 +174,7 @@ fn test() {
          #.#####.##",
     );
     let grid_after_1_ref: Grid<u8> = Grid::from_lines(&after_1_str).unwrap();
-    assert_eq!(grid_after_1_ref.data, grid_after_1.data);
+    assert_seats_eq(&grid_after_1_ref, seats.grid());
 
     let after_2_str = string_to_lines(
         "#.LL.LL.L#
@@ -206,9 +188,9 @@ fn test() {
          #.LLLLLL.L
          #.LLLLL.L#",
     );
-    let grid_after_2 = simulation_step(&grid_after_1, Hood::create2, 5);
+    assert!(seats.step());
     let grid_after_2_ref: Grid<u8> = Grid::from_lines(&after_2_str).unwrap();
-    assert_eq!(grid_after_2_ref.data, grid_after_2.data);
+    assert_seats_eq(&grid_after_2_ref, seats.grid());
 
     let after_3_str = string_to_lines(
         "#.L#.##.L#
@@ -222,9 +204,9 @@ fn test() {
         #.L#####.L
         #.L####.L#",
     );
-    let grid_after_3 = simulation_step(&grid_after_2, Hood::create2, 5);
+    assert!(seats.step());
     let grid_after_3_ref: Grid<u8> = Grid::from_lines(&after_3_str).unwrap();
-    assert_eq!(grid_after_3_ref.data, grid_after_3.data);
+    assert_seats_eq(&grid_after_3_ref, seats.grid());
 
     let after_4_str = string_to_lines(
         "#.L#.L#.L#
@@ -238,9 +220,9 @@ fn test() {
         #.LLLLL#.L
         #.L#LL#.L#",
     );
-    let grid_after_4 = simulation_step(&grid_after_3, Hood::create2, 5);
+    assert!(seats.step());
     let grid_after_4_ref: Grid<u8> = Grid::from_lines(&after_4_str).unwrap();
-    assert_eq!(grid_after_4_ref.data, grid_after_4.data);
+    assert_seats_eq(&grid_after_4_ref, seats.grid());
 
     let after_5_str = string_to_lines(
         "#.L#.L#.L#
@@ -254,8 +236,26 @@ fn test() {
         #.LLLLL#.L
         #.L#LL#.L#",
     );
-    let grid_after_5 = simulation_step(&grid_after_4, Hood::create2, 5);
+    assert!(seats.step());
     let grid_after_5_ref: Grid<u8> = Grid::from_lines(&after_5_str).unwrap();
-    assert_eq!(grid_after_5_ref.data, grid_after_5.data);
+    assert_seats_eq(&grid_after_5_ref, seats.grid());
     assert_eq!(run(&input, TaskOfDay::Second).unwrap(), 26);
+
+    fn generations_to_string<G>(grid: &Grid<u8>, gather: G, max_annoyance: usize) -> String
+    where
+        G: Gather<u8> + Sync,
+    {
+        let mut res = format!("== generation 0 ==\n{}\n", grid);
+        let mut seats = Automaton::with_gather(grid.clone(), gather, seat_rule(max_annoyance));
+        seats.on_generation(|generation, new_grid| {
+            res.push_str(&format!("== generation {} ==\n{}\n", generation, new_grid))
+        });
+        seats.run_until_stable();
+        drop(seats);
+        res
+    }
+    let first = generations_to_string(&grid, Neighborhood::Moore, 4);
+    assert_snapshot("day11_generations_first", &first);
+    let second = generations_to_string(&grid, visible_seats, 5);
+    assert_snapshot("day11_generations_second", &second);
 }
@@ -5,11 +5,19 @@ fn ts_id_diff<I: PrimInt>(bus_id: I, time_stamp: I) -> I {
     bus_id * (time_stamp / bus_id + I::one()) - time_stamp
 }
 
-pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<u128> {
+pub fn run_stream<I, S>(mut input: I, part: TaskOfDay) -> Option<u128>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    // only the first two lines are relevant, the rest of the stream is never read
+    let estimated_arrival = input.next()?;
+    let bus_ids = input.next()?;
+    let bus_ids = bus_ids.as_ref();
     match part {
         TaskOfDay::First => {
-            let estimated_arrival = input[0].parse::<i32>().unwrap();
-            let min_tuple = input[1]
+            let estimated_arrival = estimated_arrival.as_ref().parse::<i32>().unwrap();
+            let min_tuple = bus_ids
                 .split(',')
                 .filter(|s| s != &"x")
                 .map(|s| s.parse::<i32>().unwrap())
@@ -18,7 +26,7 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<u128> {
             Some((min_tuple.0 * min_tuple.1) as u128)
         }
         TaskOfDay::Second => {
-            let offset_id_pairs = input[1]
+            let offset_id_pairs = bus_ids
                 .split(',')
                 .enumerate()
                 .filter(|(_, s)| s != &"x")
@@ -38,6 +46,10 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<u128> {
     }
 }
 
+pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<u128> {
+    run_stream(input.iter(), part)
+}
+
 #[test]
 fn test() {
     use super::common::string_to_lines;
@@ -22,6 +22,12 @@ impl RuleOp {
         if msgs.len() == 0 {
             return msgs.clone();
         }
+        let res = self.eval_nonempty(msgs, rules);
+        trace!("rule {:?} on {:?} leaves {:?}", self, msgs, res);
+        res
+    }
+
+    fn eval_nonempty<'a>(&self, msgs: &Vec<&'a str>, rules: &Vec<RuleOp>) -> Vec<&'a str> {
         match self {
             RuleOp::Idx(idx) => rules[*idx].eval(msgs, rules),
             RuleOp::Char(c) => msgs
@@ -77,10 +83,8 @@ ops_factory!(
     )
 );
 
-pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
+fn parse_rules(rules_raw: &[String], part: TaskOfDay) -> Option<Vec<RuleOp>> {
     // basic idea is that numbers in rules are operators, use exmex with operator literals
-    let split_pos = find_split_positions(input);
-    let rules_raw = &input[0..split_pos[0]];
     let mut rules_strs = vec!["".to_string(); rules_raw.len()];
     for rule_raw in rules_raw.iter() {
         let rule_raw = match part {
@@ -112,6 +116,12 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
         })
         .collect::<ExResult<Vec<_>>>()
         .ok()?;
+    Some(rules)
+}
+
+pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
+    let split_pos = find_split_positions(input);
+    let rules = parse_rules(&input[0..split_pos[0]], part)?;
     let messages = &input[split_pos[0] + 1..];
     Some(
         messages
@@ -129,6 +139,7 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
 #[test]
 fn test_day_19() {
     use super::common::string_to_lines;
+    use super::snapshot::assert_snapshot;
 
     let input = string_to_lines(
         "0: 4 1 5
@@ -147,6 +158,17 @@ fn test_day_19() {
     );
     assert_eq!(run(&input, TaskOfDay::First), Some(2usize));
 
+    let rules_to_string = |rules: Vec<RuleOp>| {
+        rules
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| format!("{}: {:?}\n", i, rule))
+            .collect::<String>()
+    };
+    let rules_raw = &input[0..find_split_positions(&input)[0]];
+    let rules = parse_rules(rules_raw, TaskOfDay::First).unwrap();
+    assert_snapshot("day19_rules", &rules_to_string(rules));
+
     // a ((a a | b b) (a b | b a)) | (a b | b a) (a a| b b)) b
     // 0(ababbb) = 5(1(4(ababbb)))
     // 4(ababbb) =  a[babbb] == a => [(a, babbb)]
@@ -1,6 +1,5 @@
 use crate::grid::{
-    AxisIterator, FlipLr, FlipUd, Grid, GridView, Identity, IdxTransform, Rot180, Rot270, Rot90,
-    Twice,
+    reverse_bits, BinaryCodec, BitGrid, Edge, FrameWriter, Grid, Orientation, PatternMatch,
 };
 
 use super::common::separate_by_blanks;
@@ -37,7 +36,7 @@ static ALL_DIRS: [Dir; 4] = [Dir::N, Dir::E, Dir::S, Dir::W];
 #[derive(Clone, Debug)]
 struct Node {
     id: i32,
-    grid: Grid<u8>,
+    grid: BitGrid,
     n: Option<i32>,
     e: Option<i32>,
     s: Option<i32>,
@@ -70,7 +69,7 @@ impl Node {
     pub fn from_grid(id: i32, grid: Grid<u8>) -> Node {
         Node {
             id: id,
-            grid: grid,
+            grid: BitGrid::from_grid(&grid),
             n: None,
             e: None,
             s: None,
@@ -78,13 +77,13 @@ impl Node {
         }
     }
 
-    fn get_axis_iter(&self, dir: Dir) -> AxisIterator<u8, Identity> {
-        match dir {
-            Dir::N => AxisIterator::make_row(0, &self.grid, 1),
-            Dir::E => AxisIterator::make_col(self.grid.cols - 1, &self.grid, 1),
-            Dir::S => AxisIterator::make_row(self.grid.rows - 1, &self.grid, 1),
-            Dir::W => AxisIterator::make_col(0, &self.grid, 1),
-        }
+    fn edge(&self, dir: Dir) -> u64 {
+        self.grid.edge(match dir {
+            Dir::N => Edge::Top,
+            Dir::E => Edge::Right,
+            Dir::S => Edge::Bottom,
+            Dir::W => Edge::Left,
+        })
     }
 }
 
@@ -93,19 +92,15 @@ fn str_to_id_grid(s: &str) -> Option<(i32, Grid<u8>)> {
     let id = NUMERIC.captures(&lines[0]).unwrap()[0]
         .parse::<i32>()
         .unwrap();
-    let grid = Grid::from_lines(&lines[1..])?;
+    let grid = Grid::from_lines_with(&lines[1..], &BinaryCodec).ok()?;
     Some((id, grid))
 }
 
-fn match_axis(
-    iter1: &AxisIterator<u8, Identity>,
-    iter2: &AxisIterator<u8, Identity>,
-) -> Option<bool> {
-    let iter1_clone = iter1.clone();
-    let iter2_clone = iter2.clone();
-    if izip!(iter1_clone, iter2_clone).all(|(i1, i2)| i1 == i2) {
+/// Whether the edges match reversed, None if they do not match at all.
+fn match_edges(edge1: u64, edge2: u64, len: usize) -> Option<bool> {
+    if edge1 == edge2 {
         Some(false)
-    } else if izip!(iter1.clone().rev(), iter2.clone()).all(|(i1, i2)| i1 == i2) {
+    } else if reverse_bits(edge1, len) == edge2 {
         Some(true)
     } else {
         None
@@ -113,28 +108,29 @@ fn match_axis(
 }
 
 enum MatchResult {
-    NewGrid(Grid<u8>),
+    NewGrid(BitGrid),
     SameGrid,
 }
 
-fn transform_grid(grid: &Grid<u8>, ori: Dir, dir: Dir, flipped: bool) -> Grid<u8> {
+fn transform_grid(grid: &BitGrid, ori: Dir, dir: Dir, flipped: bool) -> BitGrid {
     let aligned = match (ori, dir) {
-        (Dir::N, Dir::N) | (Dir::E, Dir::E) | (Dir::S, Dir::S) | (Dir::W, Dir::W) => grid.clone(),
-        (Dir::W, Dir::S) | (Dir::E, Dir::N) => grid.rot90(),
-        (Dir::W, Dir::E) | (Dir::E, Dir::W) => grid.fliplr(),
-        (Dir::N, Dir::S) | (Dir::S, Dir::N) => grid.flipud(),
-        (Dir::N, Dir::E) | (Dir::S, Dir::W) => grid.rot270(),
-        (Dir::S, Dir::E) | (Dir::N, Dir::W) => grid.transform::<Twice<Rot90, FlipUd>>(),
-        (Dir::E, Dir::S) | (Dir::W, Dir::N) => grid.transform::<Twice<Rot270, FlipLr>>(),
-    };
-    if flipped {
-        match dir {
-            Dir::N | Dir::S => aligned.fliplr(),
-            Dir::E | Dir::W => aligned.flipud(),
+        (Dir::N, Dir::N) | (Dir::E, Dir::E) | (Dir::S, Dir::S) | (Dir::W, Dir::W) => {
+            Orientation::Identity
         }
-    } else {
-        aligned
-    }
+        (Dir::W, Dir::S) | (Dir::E, Dir::N) => Orientation::Rot90,
+        (Dir::W, Dir::E) | (Dir::E, Dir::W) => Orientation::FlipLr,
+        (Dir::N, Dir::S) | (Dir::S, Dir::N) => Orientation::FlipUd,
+        (Dir::N, Dir::E) | (Dir::S, Dir::W) => Orientation::Rot270,
+        (Dir::S, Dir::E) | (Dir::N, Dir::W) | (Dir::E, Dir::S) | (Dir::W, Dir::N) => {
+            Orientation::Transpose
+        }
+    };
+    let orientation = match (flipped, dir) {
+        (false, _) => aligned,
+        (true, Dir::N) | (true, Dir::S) => aligned.then(Orientation::FlipLr),
+        (true, Dir::E) | (true, Dir::W) => aligned.then(Orientation::FlipUd),
+    };
+    grid.orient(orientation)
 }
 
 fn match_node(
@@ -144,11 +140,14 @@ fn match_node(
     node2_grid_fixed: bool,
 ) -> Option<MatchResult> {
     // returns geometry of second node if it fits
-    let iters1 = node1.get_axis_iter(node1_dir);
+    let edge1 = node1.edge(node1_dir);
+    let len = match node1_dir {
+        Dir::N | Dir::S => node1.grid.cols,
+        Dir::E | Dir::W => node1.grid.rows,
+    };
     let node2_dir = node1_dir.invert();
     if node2_grid_fixed {
-        let iters2 = node2.get_axis_iter(node2_dir);
-        let unflipped_match = match_axis(&iters1, &iters2);
+        let unflipped_match = match_edges(edge1, node2.edge(node2_dir), len);
         return match unflipped_match {
             Some(m) => {
                 if m {
@@ -161,8 +160,7 @@ fn match_node(
         };
     }
     for node2_ori in ALL_DIRS.iter() {
-        let iters2 = node2.get_axis_iter(*node2_ori);
-        let are_axis_matching = match_axis(&iters1, &iters2);
+        let are_axis_matching = match_edges(edge1, node2.edge(*node2_ori), len);
         if are_axis_matching.is_some() {
             let flipped = are_axis_matching?;
             return Some(MatchResult::NewGrid(transform_grid(
@@ -196,17 +194,17 @@ fn get_corners(nodes: &BTreeMap<i32, Node>) -> Vec<i32> {
         .collect::<Vec<i32>>()
 }
 
-fn _print_hood(node_id: i32, nodes: &BTreeMap<i32, Node>) {
+fn trace_hood(node_id: i32, nodes: &BTreeMap<i32, Node>) {
     let un = |id: Option<i32>| id.unwrap_or(-1);
-    println!("\t{:04}", un(nodes[&node_id].n));
-    println!(
-        "{:04}\t{:04}\t{:04}",
-        un(nodes[&node_id].w),
-        node_id,
-        un(nodes[&node_id].e)
+    let node = &nodes[&node_id];
+    trace!(
+        "hood of {:04}: n {:04}, e {:04}, s {:04}, w {:04}",
+        node.id,
+        un(node.n),
+        un(node.e),
+        un(node.s),
+        un(node.w)
     );
-    println!("\t{:04}", un(nodes[&node_id].s));
-    println!("");
 }
 
 struct NodeIterator<'a> {
@@ -239,95 +237,76 @@ fn node_col_iter<'a>(col_anchor: i32, nodes: &'a BTreeMap<i32, Node>) -> NodeIte
     }
 }
 
-fn merge_grids(nodes: &BTreeMap<i32, Node>) -> Grid<u8> {
+/// The tiles without their borders, which are only there for matching, in one bit grid.
+fn merge_grids(nodes: &BTreeMap<i32, Node>) -> BitGrid {
     let row_anchor0 = *nodes
         .iter()
         .find(|(_, n)| n.get_neighbor(Dir::N).is_none() && n.get_neighbor(Dir::W).is_none())
         .unwrap()
         .0;
-    let grids_in_a_row = node_row_iter(row_anchor0, &nodes).count();
-    let grids_in_a_col = nodes.len() / grids_in_a_row;
-    let rows_per_view = nodes[&row_anchor0].grid.rows - 2;
-    let cols_per_view = nodes[&row_anchor0].grid.cols - 2;
-    let res_rows = grids_in_a_row * (rows_per_view);
-    let res_cols = grids_in_a_col * (cols_per_view);
-    let mut res: Grid<u8> = Grid {
-        rows: res_rows,
-        cols: res_cols,
-        data: vec![0; res_rows * res_cols],
-    };
-
-    for (cur_row, row_anchor) in node_col_iter(row_anchor0, &nodes).enumerate() {
-        let row_offset = cur_row * rows_per_view;
-        let row_iter = node_row_iter(row_anchor, &nodes);
-        for (grids_col, current_id) in row_iter.enumerate() {
-            let view = &nodes[&current_id]
-                .grid
-                .view(1..rows_per_view + 1, 1..cols_per_view + 1);
-            let col_offset = grids_col * cols_per_view;
-            for (r, c) in iproduct!(0..view.rows(), 0..view.cols()) {
-                res[row_offset + r][col_offset + c] = *view.at(r, c);
+    let tiles = node_col_iter(row_anchor0, &nodes)
+        .map(|row_anchor| {
+            node_row_iter(row_anchor, &nodes)
+                .map(|id| &nodes[&id].grid)
+                .collect::<Vec<&BitGrid>>()
+        })
+        .collect::<Vec<_>>();
+    let (rows, cols) = (tiles[0][0].rows - 2, tiles[0][0].cols - 2);
+    let mut res = BitGrid::new(tiles.len() * rows, tiles[0].len() * cols);
+    for (i, tile_row) in tiles.iter().enumerate() {
+        for (j, tile) in tile_row.iter().enumerate() {
+            for r in 0..rows {
+                res.or_bits(i * rows + r, j * cols, cols, tile.bits(r + 1, 1, cols));
             }
         }
     }
     res
 }
 
-fn roughness_per_orientation<'a, TF: IdxTransform>(
-    mut grid: Grid<u8>,
-    monster: GridView<'a, u8, TF>,
-) -> (usize, Grid<u8>) {
-    let row_end = grid.rows + 1 - monster.rows();
-    let col_end = grid.cols + 1 - monster.cols();
-    let mut monster_buffer: Grid<u8> = monster.to_grid();
-    let n_monster_nonzeros = iproduct!(0..monster.rows(), 0..monster.cols())
-        .filter(|(r, c)| *monster.at(*r, *c) > 0)
-        .count();
-
-    for (r, c) in iproduct!(0..row_end, 0..col_end) {
-        for (r_monster, c_monster) in iproduct!(0..monster.rows(), 0..monster.cols()) {
-            let monster_val = *monster.at(r_monster, c_monster);
-            monster_buffer[r_monster][c_monster] =
-                ((monster_val > 0u8) && (*grid.at(r + r_monster, c + c_monster)) > 0u8) as u8;
-        }
-        let n_monster_buffer_nonzeros = monster_buffer.data.iter().filter(|i| **i > 0).count();
-        if n_monster_buffer_nonzeros == n_monster_nonzeros {
-            for (r_monster, c_monster) in iproduct!(0..monster.rows(), 0..monster.cols()) {
-                let monster_val = *monster.at(r_monster, c_monster);
-                grid[r + r_monster][c + c_monster] = match monster_val {
-                    0 => *grid.at(r + r_monster, c + c_monster),
-                    _ => 0u8,
-                };
+fn sea_monster() -> Grid<u8> {
+    Grid::from_lines_with(
+        &[
+            "..................#.",
+            "#....##....##....###",
+            ".#..#..#..#..#..#...",
+        ],
+        &BinaryCodec,
+    )
+    .unwrap()
+}
+
+fn roughness(grid: &BitGrid, monster: &BitGrid) -> usize {
+    let mut monsters = BitGrid::new(grid.rows, grid.cols);
+    let mut found: Vec<PatternMatch> = vec![];
+    for orientation in Orientation::ALL.iter() {
+        let oriented = monster.orient(*orientation);
+        let matches = grid.find_pattern(&oriented);
+        if matches.len() > 0 {
+            debug!("found {} monsters in orientation {:?}", matches.len(), orientation);
+            for (row, col) in matches {
+                monsters.or_pattern(&oriented, row, col);
+                found.push(PatternMatch {
+                    row: row,
+                    col: col,
+                    orientation: *orientation,
+                });
             }
+            break;
         }
     }
-    (grid.data.iter().filter(|i| **i > 0).count(), grid)
-}
-
-fn roughness(mut grid: Grid<u8>, monster: &Grid<u8>) -> usize {
-    let roughness_calls: Vec<Box<dyn FnOnce(Grid<u8>) -> (usize, Grid<u8>)>> = vec![
-        Box::new(|g| roughness_per_orientation(g, monster.as_view())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<Rot90>())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<Rot180>())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<Rot270>())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<FlipLr>())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<Twice<Rot90, FlipLr>>())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<Twice<Rot180, FlipLr>>())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<Twice<Rot270, FlipLr>>())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<FlipUd>())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<Twice<Rot90, FlipUd>>())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<Twice<Rot180, FlipUd>>())),
-        Box::new(|g| roughness_per_orientation(g, monster.as_tf_view::<Twice<Rot270, FlipUd>>())),
-    ];
-    let n_nonzeros = grid.data.iter().filter(|i| **i > 0).count();
-    for call in roughness_calls {
-        let res = call(grid);
-        if res.0 < n_nonzeros {
-            return res.0;
+    if let Some(mut writer) = FrameWriter::from_env("day20_monsters", 4) {
+        let sea_color = |v: u8| match v {
+            0 => [10, 30, 90],
+            1 => [90, 160, 220],
+            _ => [240, 200, 30],
+        };
+        let mut sea: Grid<u8> = grid.to_grid();
+        sea.mark_matches(&monster.to_grid(), &found, Some(0), 2);
+        if let Err(e) = writer.write_frame(&sea, sea_color) {
+            error!("Could not write image: {}", e);
         }
-        grid = res.1;
     }
-    n_nonzeros
+    grid.and_not(&monsters).count_ones()
 }
 
 fn collect_nodes(grids: &Vec<String>) -> BTreeMap<i32, Node> {
@@ -340,10 +319,8 @@ fn collect_nodes(grids: &Vec<String>) -> BTreeMap<i32, Node> {
         .collect::<BTreeMap<i32, Node>>()
 }
 
-pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
-    let input_grids = separate_by_blanks(&input, "\n");
-    let mut nodes = collect_nodes(&input_grids);
-
+/// Connects all nodes with their neighbors and orients their grids accordingly.
+fn arrange_nodes(nodes: &mut BTreeMap<i32, Node>) -> Option<()> {
     let mut floatings = nodes.keys().map(|id| *id).collect::<Vec<i32>>();
     let mut fixeds: Vec<i32> = Vec::with_capacity(0);
 
@@ -370,6 +347,7 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
             if flo_match.is_some() {
                 let (fix_dir, node_match) = flo_match?;
                 let flo_dir = fix_dir.invert();
+                debug!("matched tile {} at {:?} of tile {}", flo, fix_dir, fix);
                 nodes.get_mut(fix)?.set_neighbor(*fix_dir, *flo);
                 nodes.get_mut(flo)?.set_neighbor(flo_dir, *fix);
                 let new_grid = match node_match? {
@@ -379,6 +357,7 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
                 nodes.get_mut(flo)?.grid = new_grid.clone();
 
                 to_be_moved.push(*flo);
+                trace_hood(*fix, nodes);
             }
         }
         for item in to_be_moved {
@@ -388,11 +367,17 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
             }
         }
     }
+    Some(())
+}
+
+pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
+    let input_grids = separate_by_blanks(&input, "\n");
+    let mut nodes = collect_nodes(&input_grids);
+    arrange_nodes(&mut nodes)?;
     let corners = get_corners(&nodes);
     if corners.clone().len() != 4 {
         for corner in &corners {
-            println!("===== NODE =====");
-            println!("{:#?}", &nodes[corner]);
+            error!("corner node {:#?}", &nodes[corner]);
         }
         panic!("We have exactly 4 corners not {}.", corners.clone().len())
     }
@@ -400,20 +385,19 @@ pub fn run(input: &Vec<String>, part: TaskOfDay) -> Option<usize> {
     match part {
         TaskOfDay::First => Some(corners.iter().map(|id| *id as usize).product()),
         TaskOfDay::Second => {
-            let merged_grid = merge_grids(&nodes);
-            let monster: Grid<u8> = Grid::from_lines(&vec![
-                "..................#.".to_string(),
-                "#....##....##....###".to_string(),
-                ".#..#..#..#..#..#...".to_string(),
-            ])
-            .unwrap();
-            Some(roughness(merged_grid, &monster))
+            Some(roughness(
+                &merge_grids(&nodes),
+                &BitGrid::from_grid(&sea_monster()),
+            ))
         }
     }
 }
 
 #[test]
 fn test_day_20() {
+    use super::snapshot::assert_snapshot;
+    use crate::grid::PatternOptions;
+
     let input = string_to_lines(
         "Tile 2311:
         ..##.#..#.
@@ -536,11 +520,10 @@ fn test_day_20() {
     assert_eq!(grid[0usize][0usize], 0);
 
     // 4 south matches 3 north
-    let it4north = AxisIterator::make_row(grids[4].1.rows - 1, &grids[4].1, 1);
-    let it3south = AxisIterator::make_row(0, &grids[3].1, 1);
-    assert!(match_axis(&it4north, &it3south).is_some());
-    let it31 = AxisIterator::make_row(1, &grids[3].1, 1);
-    assert!(!match_axis(&it4north, &it31).is_some());
+    let bits4 = BitGrid::from_grid(&grids[4].1);
+    let bits3 = BitGrid::from_grid(&grids[3].1);
+    assert!(match_edges(bits4.edge(Edge::Bottom), bits3.edge(Edge::Top), 10).is_some());
+    assert!(!match_edges(bits4.edge(Edge::Bottom), bits3.row_bits(1), 10).is_some());
 
     let grid_1489 = grids.iter().find(|(i, _)| *i == 1489).unwrap();
     let grid_1951 = grids.iter().find(|(i, _)| *i == 1951).unwrap();
@@ -554,12 +537,7 @@ fn test_day_20() {
     let grid_2971 = grids.iter().find(|(i, _)| *i == 2971).unwrap();
     let node_2971 = Node::from_grid(grid_2971.0, grid_2971.1.clone());
 
-    let iters1 = node_1489.get_axis_iter(Dir::W);
-    let iters2 = node_2971.get_axis_iter(Dir::E);
-    assert_eq!(
-        iters1.map(|x| *x).collect::<Vec<u8>>(),
-        iters2.map(|x| *x).collect::<Vec<u8>>()
-    );
+    assert_eq!(node_1489.edge(Dir::W), node_2971.edge(Dir::E));
     assert!(match_node(&node_1489, &node_2971, Dir::W, false).is_some());
 
     assert!(match_node(&node_1951, &node_1489, Dir::N, false).is_none());
@@ -583,4 +561,26 @@ fn test_day_20() {
 
     assert_eq!(run(&input, TaskOfDay::First), Some(20899048083289));
     assert_eq!(run(&input, TaskOfDay::Second), Some(273));
+
+    let mut nodes = collect_nodes(&input_grids);
+    arrange_nodes(&mut nodes).unwrap();
+    let bits = merge_grids(&nodes);
+    let merged = bits.to_grid::<u8>();
+    assert_snapshot("day20_merged_image", &format!("{}\n", merged.display_with(&BinaryCodec)));
+
+    // the word-level search finds the same monsters as the generic template matching
+    let monster = sea_monster();
+    let options = PatternOptions {
+        orientations: Orientation::ALL.to_vec(),
+        mask_value: Some(0),
+        ..PatternOptions::default()
+    };
+    let expected = merged.find_pattern(&monster, &options);
+    assert_eq!(expected.len(), 2);
+    let oriented = BitGrid::from_grid(&monster).orient(expected[0].orientation);
+    let found = bits.find_pattern(&oriented);
+    assert_eq!(
+        found,
+        expected.iter().map(|m| (m.row, m.col)).collect::<Vec<_>>()
+    );
 }
@@ -2,6 +2,40 @@ use num::Num;
 use std::ops::{Index, IndexMut};
 use std::{fmt, ops::Range};
 
+mod automaton;
+mod bit_grid;
+mod codec;
+mod components;
+mod grid_n;
+mod image;
+mod iter;
+mod neighbors;
+mod orientation;
+mod path;
+mod pattern;
+mod point;
+mod ray;
+mod reshape;
+mod sparse;
+mod view_mut;
+mod wrap;
+pub use automaton::{Automaton, Callback, Cycle, Gather};
+pub use bit_grid::{reverse_bits, BitGrid, Edge};
+pub use codec::{BinaryCodec, CellCodec, CharMap, GridParseError, SeatCodec, UNKNOWN_CELL};
+pub use components::{component_stats, ComponentStats};
+pub use grid_n::{moore_offsets_n, GridN, Strided};
+pub use image::{FrameWriter, IMAGES_ENV_VAR};
+pub use iter::{AxesIterator, LineIterator};
+pub use neighbors::{Boundary, Neighborhood, Neighbors, MOORE_OFFSETS, VON_NEUMANN_OFFSETS};
+pub use orientation::{Orientation, OrientationTf};
+pub use path::{chebyshev_distance, manhattan_distance, PathResult, UNREACHABLE};
+pub use pattern::{PatternMatch, PatternOptions};
+pub use point::Point;
+pub use ray::RayHit;
+pub use sparse::{Bounds, SparseGrid};
+pub use view_mut::GridViewMut;
+pub use wrap::{TiledView, WrapAxes, WrappingView};
+
 pub trait DataType: Num + Clone + Copy + fmt::Debug {}
 impl<T: Num + Clone + Copy + fmt::Debug> DataType for T {}
 
@@ -200,6 +234,24 @@ impl<'a, T: DataType, TF: IdxTransform> GridView<'a, T, TF> {
         &self.grid.data[shifted_row * self.grid.cols..(shifted_row + 1) * self.grid.cols]
             [self.col_start..self.col_end][t_col]
     }
+    pub fn to_lines_with<C: CellCodec<T>>(&self, codec: &C) -> Vec<String> {
+        (0..self.rows())
+            .map(|r| {
+                (0..self.cols())
+                    .map(|c| codec.encode(*self.at(r, c)).unwrap_or(UNKNOWN_CELL))
+                    .collect::<String>()
+            })
+            .collect()
+    }
+    pub fn display_with<'c, C: CellCodec<T>>(&self, codec: &'c C) -> GridDisplay<'a, 'c, T, TF, C>
+    where
+        TF: Clone,
+    {
+        GridDisplay {
+            view: self.clone(),
+            codec: codec,
+        }
+    }
     pub fn to_grid(&self) -> Grid<T> {
         let data = iproduct!(0..self.rows(), 0..self.cols())
             .map(|(r, c)| *self.at(r, c))
@@ -225,35 +277,108 @@ impl<T: DataType> IndexMut<usize> for Grid<T> {
 }
 
 impl<T: DataType> Grid<T> {
+    /// Parses seat maps, see `SeatCodec`.
     pub fn from_lines(lines: &[String]) -> Option<Grid<T>> {
-        let lengths = lines
-            .iter()
-            .map(|line| line.chars().count())
-            .collect::<Vec<usize>>();
-        let cols = lengths.iter().min()?;
-        let rows = lines.len();
-        if cols != lengths.iter().max()? {
-            return None;
+        Grid::from_lines_with(lines, &SeatCodec).ok()
+    }
+
+    /// Renders seat maps, such that `from_lines(&grid.to_lines())` reproduces the grid.
+    pub fn to_lines(&self) -> Vec<String> {
+        self.to_lines_with(&SeatCodec)
+    }
+
+    pub fn to_lines_with<C: CellCodec<T>>(&self, codec: &C) -> Vec<String> {
+        self.as_view().to_lines_with(codec)
+    }
+
+    pub fn display_with<'a, 'c, C: CellCodec<T>>(
+        &'a self,
+        codec: &'c C,
+    ) -> GridDisplay<'a, 'c, T, Identity, C> {
+        GridDisplay {
+            view: self.as_view(),
+            codec: codec,
         }
-        Some(Grid {
-            cols: *cols,
-            rows: rows,
-            data: lines
-                .iter()
-                .map(|line| {
-                    line.chars().map(|c| match c {
-                        '.' => T::zero(),
-                        'L' => T::one(),
-                        '#' => T::one() + T::one(),
-                        _ => panic!("Unknown character '{}'", c),
-                    })
-                })
-                .flatten()
-                .collect::<Vec<T>>(),
+    }
+
+    pub fn from_lines_with<S: AsRef<str>, C: CellCodec<T>>(
+        lines: &[S],
+        codec: &C,
+    ) -> Result<Grid<T>, GridParseError> {
+        let cols = lines
+            .first()
+            .ok_or(GridParseError::Empty)?
+            .as_ref()
+            .chars()
+            .count();
+        let mut data: Vec<T> = Vec::with_capacity(cols * lines.len());
+        for (row, line) in lines.iter().enumerate() {
+            let line = line.as_ref();
+            let found = line.chars().count();
+            if found != cols {
+                return Err(GridParseError::RaggedRow {
+                    row: row,
+                    expected: cols,
+                    found: found,
+                });
+            }
+            for (col, c) in line.chars().enumerate() {
+                data.push(
+                    codec
+                        .decode(c)
+                        .ok_or(GridParseError::UnknownChar { row: row, col: col, c: c })?,
+                );
+            }
+        }
+        Ok(Grid {
+            cols: cols,
+            rows: lines.len(),
+            data: data,
         })
     }
 }
 
+/// Renders a grid in puzzle notation, one line per row.
+pub struct GridDisplay<'a, 'c, T: DataType, TF: IdxTransform, C: CellCodec<T>> {
+    view: GridView<'a, T, TF>,
+    codec: &'c C,
+}
+
+impl<'a, 'c, T: DataType, TF: IdxTransform, C: CellCodec<T>> fmt::Display
+    for GridDisplay<'a, 'c, T, TF, C>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = self.view.to_lines_with(self.codec);
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl<'a, T: DataType, TF: IdxTransform + Clone> fmt::Display for GridView<'a, T, TF> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_with(&SeatCodec).fmt(f)
+    }
+}
+
+impl<T: DataType> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.as_view().fmt(f)
+    }
+}
+
+/// Puts two renderings next to each other, e.g., for expected and actual grids.
+pub fn side_by_side(left: &[String], right: &[String]) -> String {
+    let width = left.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let empty = "".to_string();
+    (0..left.len().max(right.len()))
+        .map(|i| {
+            let l = left.get(i).unwrap_or(&empty);
+            let r = right.get(i).unwrap_or(&empty);
+            format!("{:w$} | {}", l, r, w = width)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 impl<'a, T: DataType, TF: IdxTransform> fmt::Debug for GridView<'a, T, TF> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut res = format!("rows {}, cols {}\n", self.rows(), self.cols());
@@ -283,10 +408,16 @@ impl<T: DataType> fmt::Debug for Grid<T> {
     }
 }
 
-fn start_end(start: isize, end: isize, step: isize) -> (isize, isize) {
+/// First index and number of visited indices when walking 0..len with step, a negative
+/// step starts at the end.
+fn first_count(len: usize, step: isize) -> (isize, usize) {
+    if step == 0 {
+        panic!("Step must not be 0");
+    }
+    let stride = step.unsigned_abs();
     (
-        if step > 0 { start } else { end - 1 },
-        if step > 0 { end } else { start - 1 },
+        if step > 0 { 0 } else { len as isize - 1 },
+        len.div_ceil(stride),
     )
 }
 #[derive(Clone, Copy)]
@@ -299,7 +430,7 @@ pub enum Axis {
 pub struct AxisIterator<'a, T: DataType, TF: IdxTransform> {
     view: GridView<'a, T, TF>,
     start: isize,
-    end: isize,
+    remaining: usize,
     step: isize,
     axis: Axis,
     axis_idx: usize,
@@ -307,108 +438,83 @@ pub struct AxisIterator<'a, T: DataType, TF: IdxTransform> {
 
 impl<'a, T: DataType> AxisIterator<'a, T, Identity> {
     pub fn make_row(row: usize, grid: &'a Grid<T>, step: isize) -> AxisIterator<'a, T, Identity> {
-        let (start, end) = start_end(0, grid.cols as isize, step);
-        return AxisIterator {
-            view: grid.as_view(),
-            start: start,
-            end: end,
-            step: step,
-            axis: Axis::Row,
-            axis_idx: row,
-        };
+        AxisIterator::make_row_view(row, grid.as_view(), step)
     }
     pub fn make_col(col: usize, grid: &'a Grid<T>, step: isize) -> AxisIterator<'a, T, Identity> {
-        let (start, end) = start_end(0, grid.rows as isize, step);
-        return AxisIterator {
-            view: grid.as_view(),
-            start: start,
-            end: end,
-            step: step,
-            axis: Axis::Col,
-            axis_idx: col,
-        };
+        AxisIterator::make_col_view(col, grid.as_view(), step)
     }
-} 
+}
 
 impl<'a, T: DataType, TF: IdxTransform> AxisIterator<'a, T, TF> {
 
+    /// Every |step|-th cell of the row, backwards from the last col for negative steps.
     pub fn make_row_view(
         row: usize,
         grid_view: GridView<'a, T, TF>,
         step: isize,
     ) -> AxisIterator<'a, T, TF> {
-        let (start, end) = start_end(0isize, grid_view.cols() as isize,
-            step,
-        );
+        let (start, remaining) = first_count(grid_view.cols(), step);
 
         return AxisIterator {
             view: grid_view,
             start: start,
-            end: end,
+            remaining: remaining,
             step: step,
             axis: Axis::Row,
             axis_idx: row,
         };
     }
+    /// Every |step|-th cell of the col, backwards from the last row for negative steps.
     pub fn make_col_view(
         col: usize,
         grid_view: GridView<'a, T, TF>,
         step: isize,
     ) -> AxisIterator<'a, T, TF> {
-        let (start, end) = start_end(0isize, grid_view.rows() as isize,
-            step,
-        );
+        let (start, remaining) = first_count(grid_view.rows(), step);
 
         return AxisIterator {
             view: grid_view,
             start: start,
-            end: end,
+            remaining: remaining,
             step: step,
             axis: Axis::Col,
             axis_idx: col,
         };
     }
+
+    fn get(&self, idx: isize) -> &'a T {
+        match self.axis {
+            Axis::Col => self.view.at(idx as usize, self.axis_idx),
+            Axis::Row => self.view.at(self.axis_idx, idx as usize),
+        }
+    }
 }
 
 impl<'a, T: DataType, TF: IdxTransform> Iterator for AxisIterator<'a, T, TF> {
     type Item = &'a T;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.start == self.end {
+        if self.remaining == 0 {
             return None;
         }
-        let nxt = self.start + self.step;
-        match self.axis {
-            Axis::Col => {
-                let res: Option<&'a T> = Some(self.view.at(self.start as usize, self.axis_idx));
-                self.start = nxt;
-                res
-            }
-            Axis::Row => {
-                let res = Some(self.view.at(self.axis_idx, self.start as usize));
-                self.start = nxt;
-                res
-            }
-        }
+        let res = self.get(self.start);
+        self.start += self.step;
+        self.remaining -= 1;
+        Some(res)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
 impl<'a, T: DataType, TF: IdxTransform> DoubleEndedIterator for AxisIterator<'a, T, TF> {
     fn next_back(&mut self) -> Option<Self::Item> {
-        if self.start == self.end {
+        if self.remaining == 0 {
             return None;
         }
-        let nxt = self.end - self.step;
-        match self.axis {
-            Axis::Col => {
-                self.end = nxt;
-                Some(self.view.at(self.end as usize, self.axis_idx))
-            }
-            Axis::Row => {
-                self.end = nxt;
-                Some(self.view.at(self.axis_idx, self.end as usize))
-            }
-        }
+        self.remaining -= 1;
+        Some(self.get(self.start + self.remaining as isize * self.step))
     }
 }
+impl<'a, T: DataType, TF: IdxTransform> ExactSizeIterator for AxisIterator<'a, T, TF> {}
 
 #[test]
 fn test_grid() {
@@ -418,7 +524,7 @@ fn test_grid() {
         data: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
     };
     let grid_14_13 = grid_axis_iter_test.view(1..4, 1..3);
-    println!(
+    debug!(
         "===GRID\n{:?}\n===GRIDVIEW\n{:?}",
         grid_axis_iter_test, grid_14_13
     );
@@ -454,10 +560,10 @@ fn test_grid() {
     let rot_90_test = grid.rot90();
     let rot_180_test = grid.rot180();
     let rot_270_test = grid.rot270();
-    println!("rot_test\n{:#?}", grid);
-    println!("rot_90\n{:#?}", rot_90_test);
-    println!("rot_180\n{:#?}", rot_180_test);
-    println!("rot_270\n{:#?}", rot_270_test);
+    debug!("rot_test\n{:#?}", grid);
+    debug!("rot_90\n{:#?}", rot_90_test);
+    debug!("rot_180\n{:#?}", rot_180_test);
+    debug!("rot_270\n{:#?}", rot_270_test);
     assert_eq!(rot_90_test.cols, 2);
     assert_eq!(rot_90_test.rows, 3);
     assert_eq!(rot_90_test[0][0], 3);
@@ -502,4 +608,69 @@ fn test_grid() {
     assert_eq!(grid.rot90(), rot90_view.to_grid());
     let col_iter = AxisIterator::make_col_view(2, rot90_view, 1);
     assert_eq!(col_iter.collect::<Vec<&i32>>(), vec![&11, &10]);
+
+    // strides that do not divide the length
+    let wide = Grid {
+        rows: 1,
+        cols: 5,
+        data: vec![0, 1, 2, 3, 4],
+    };
+    let strided = |step| AxisIterator::make_row(0, &wide, step).cloned().collect::<Vec<i32>>();
+    assert_eq!(strided(2), vec![0, 2, 4]);
+    assert_eq!(strided(3), vec![0, 3]);
+    assert_eq!(strided(-2), vec![4, 2, 0]);
+    assert_eq!(strided(-3), vec![4, 1]);
+    assert_eq!(strided(7), vec![0]);
+    let mut iter = AxisIterator::make_row(0, &wide, 2);
+    assert_eq!(iter.len(), 3);
+    assert_eq!((iter.next_back(), iter.next()), (Some(&4), Some(&0)));
+    assert_eq!((iter.len(), iter.next_back(), iter.next()), (1, Some(&2), None));
+    let col = AxisIterator::make_col_view(1, grid_14_13, -2);
+    assert_eq!(col.rev().collect::<Vec<&i32>>(), vec![&5, &11]);
+}
+
+#[test]
+fn test_from_lines() {
+    let lines = vec!["#.L", "L.#"];
+    let grid = Grid::<u8>::from_lines_with(&lines, &SeatCodec).unwrap();
+    assert_eq!(grid.data, vec![2, 0, 1, 1, 0, 2]);
+    let grid = Grid::<i32>::from_lines_with(&["#.", ".#"], &BinaryCodec).unwrap();
+    assert_eq!(grid.data, vec![1, 0, 0, 1]);
+    let codec = CharMap::new(&[('a', 3u8), ('b', 7u8)]);
+    let grid = Grid::from_lines_with(&["ab", "ba"], &codec).unwrap();
+    assert_eq!(grid.data, vec![3, 7, 7, 3]);
+
+    assert_eq!(
+        Grid::<u8>::from_lines_with(&["..", ".x"], &SeatCodec),
+        Err(GridParseError::UnknownChar { row: 1, col: 1, c: 'x' })
+    );
+    assert_eq!(
+        Grid::<u8>::from_lines_with(&["..", "..."], &SeatCodec),
+        Err(GridParseError::RaggedRow { row: 1, expected: 2, found: 3 })
+    );
+    let no_lines: [&str; 0] = [];
+    assert_eq!(
+        Grid::<u8>::from_lines_with(&no_lines, &SeatCodec),
+        Err(GridParseError::Empty)
+    );
+    assert!(Grid::<u8>::from_lines(&["L#".to_string(), "#O".to_string()]).is_none());
+}
+
+#[test]
+fn test_to_lines() {
+    let lines = vec!["#.L#".to_string(), "L..#".to_string(), "##LL".to_string()];
+    let grid = Grid::<u8>::from_lines(&lines).unwrap();
+    assert_eq!(grid.to_lines(), lines);
+    assert_eq!(Grid::<u8>::from_lines(&grid.to_lines()).unwrap(), grid);
+    assert_eq!(format!("{}", grid), "#.L#\nL..#\n##LL");
+    assert_eq!(format!("{}", grid.view(1..3, 2..4)), ".#\nLL");
+    assert_eq!(format!("{}", grid.as_tf_view::<Rot90>()), "##L\nL.L\n..#\n#L#");
+
+    let binary = Grid::<i32>::from_lines_with(&["#.", ".#"], &BinaryCodec).unwrap();
+    assert_eq!(format!("{}", binary.display_with(&BinaryCodec)), "#.\n.#");
+    assert_eq!(binary.to_lines_with(&CharMap::new(&[('x', 1)])), vec!["x?", "?x"]);
+    assert_eq!(
+        side_by_side(&["ab".to_string(), "c".to_string()], &["d".to_string()]),
+        "ab | d\nc  | "
+    );
 }
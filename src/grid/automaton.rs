@@ -0,0 +1,276 @@
+use super::{DataType, Grid, Neighborhood};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::thread;
+
+/// Collects the neighborhood of (row, col) into the buffer, which is passed empty.
+pub trait Gather<T: DataType> {
+    fn gather(&self, grid: &Grid<T>, row: usize, col: usize, buffer: &mut Vec<T>);
+}
+
+/// The neighborhood is clipped at the grid boundary.
+impl<'a, T: DataType> Gather<T> for Neighborhood<'a> {
+    fn gather(&self, grid: &Grid<T>, row: usize, col: usize, buffer: &mut Vec<T>) {
+        let (row, col) = (row as isize, col as isize);
+        for (dr, dc) in self.offsets() {
+            if let Some(value) = grid.get(row + dr, col + dc) {
+                buffer.push(*value);
+            }
+        }
+    }
+}
+
+/// For neighborhoods that are not fixed offsets, e.g., lines of sight.
+impl<T: DataType, F: Fn(&Grid<T>, usize, usize, &mut Vec<T>)> Gather<T> for F {
+    fn gather(&self, grid: &Grid<T>, row: usize, col: usize, buffer: &mut Vec<T>) {
+        self(grid, row, col, buffer)
+    }
+}
+
+/// Bands with fewer cells are not worth a thread. Measured with day 11, spawning a scoped
+/// thread costs about 28 us and stepping a cell 27 to 67 ns, i.e., a band of this size takes
+/// about 4 times longer than its thread to spawn.
+const MIN_CELLS_PER_THREAD: usize = 4096;
+
+/// Called with the generation number and the grid after every generation.
+pub type Callback<'a, T> = Box<dyn FnMut(usize, &Grid<T>) + 'a>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cycle {
+    /// first generation that repeats
+    pub start: usize,
+    /// 1 for a fixed point
+    pub period: usize,
+}
+
+/// Applies the rule, which maps a cell and its gathered neighborhood to the cell of the next
+/// generation, to all cells simultaneously. The next generation is computed into a second
+/// buffer that is swapped with the current one.
+pub struct Automaton<'a, T: DataType, G, R> {
+    current: Grid<T>,
+    next: Grid<T>,
+    generation: usize,
+    threads: usize,
+    gather: G,
+    rule: R,
+    callbacks: Vec<Callback<'a, T>>,
+}
+
+/// Computes the rows of the next generation that start at row_start into band.
+fn step_band<T: DataType, G: Gather<T>, R: Fn(T, &[T]) -> T>(
+    current: &Grid<T>,
+    gather: &G,
+    rule: &R,
+    row_start: usize,
+    band: &mut [T],
+) {
+    if current.cols == 0 {
+        return;
+    }
+    let mut buffer = Vec::with_capacity(8);
+    for (i, band_row) in band.chunks_mut(current.cols).enumerate() {
+        let r = row_start + i;
+        for (c, (cell, value)) in band_row.iter_mut().zip(current[r].iter()).enumerate() {
+            buffer.clear();
+            gather.gather(current, r, c, &mut buffer);
+            *cell = rule(*value, &buffer);
+        }
+    }
+}
+
+impl<'a, T, R> Automaton<'a, T, Neighborhood<'a>, R>
+where
+    T: DataType + Send + Sync,
+    R: Fn(T, &[T]) -> T + Sync,
+{
+    pub fn new(grid: Grid<T>, hood: Neighborhood<'a>, rule: R) -> Self {
+        Automaton::with_gather(grid, hood, rule)
+    }
+}
+
+impl<'a, T, G, R> Automaton<'a, T, G, R>
+where
+    T: DataType + Send + Sync,
+    G: Gather<T> + Sync,
+    R: Fn(T, &[T]) -> T + Sync,
+{
+    /// Closures as gather need annotated argument types.
+    pub fn with_gather(grid: Grid<T>, gather: G, rule: R) -> Self {
+        Automaton {
+            next: grid.clone(),
+            current: grid,
+            generation: 0,
+            threads: 1,
+            gather: gather,
+            rule: rule,
+            callbacks: vec![],
+        }
+    }
+
+    /// Splits the grid into at most this many bands of rows that are stepped in parallel,
+    /// each band has at least `MIN_CELLS_PER_THREAD` cells. The result is the same as with
+    /// the default of 1 thread.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    pub fn on_generation<C: FnMut(usize, &Grid<T>) + 'a>(&mut self, callback: C) {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    pub fn grid(&self) -> &Grid<T> {
+        &self.current
+    }
+    pub fn into_grid(self) -> Grid<T> {
+        self.current
+    }
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Computes the next generation. Returns false and leaves the automaton untouched if
+    /// the grid is a fixed point.
+    pub fn step(&mut self) -> bool {
+        let (current, next) = (&self.current, &mut self.next.data);
+        let (gather, rule) = (&self.gather, &self.rule);
+        let threads = self.threads.min(current.data.len() / MIN_CELLS_PER_THREAD);
+        if threads <= 1 {
+            step_band(current, gather, rule, 0, next);
+        } else {
+            let rows_per_band = current.rows.div_ceil(threads);
+            thread::scope(|scope| {
+                let bands = next.chunks_mut(rows_per_band * current.cols);
+                for (i, band) in bands.enumerate() {
+                    scope.spawn(move || step_band(current, gather, rule, i * rows_per_band, band));
+                }
+            });
+        }
+        if self.next.data == self.current.data {
+            return false;
+        }
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+        for callback in self.callbacks.iter_mut() {
+            callback(self.generation, &self.current);
+        }
+        true
+    }
+
+    /// Stops early at a fixed point and returns the number of generations computed.
+    pub fn run(&mut self, generations: usize) -> usize {
+        let start = self.generation;
+        for _ in 0..generations {
+            if !self.step() {
+                break;
+            }
+        }
+        self.generation - start
+    }
+
+    /// Returns the generation of the fixed point.
+    pub fn run_until_stable(&mut self) -> usize {
+        while self.step() {}
+        self.generation
+    }
+
+    /// Steps until a grid repeats. The grids are looked up by hash and compared, such that
+    /// hash collisions do not end the search.
+    pub fn run_until_cycle(&mut self) -> Cycle
+    where
+        T: Hash,
+    {
+        let hash_of = |grid: &Grid<T>| {
+            let mut hasher = DefaultHasher::new();
+            grid.data.hash(&mut hasher);
+            hasher.finish()
+        };
+        let first_generation = self.generation;
+        let mut history = vec![self.current.data.clone()];
+        let mut seen: HashMap<u64, Vec<usize>> = HashMap::new();
+        seen.insert(hash_of(&self.current), vec![self.generation]);
+        loop {
+            if !self.step() {
+                return Cycle {
+                    start: self.generation,
+                    period: 1,
+                };
+            }
+            let hash = hash_of(&self.current);
+            let candidates = seen.entry(hash).or_insert_with(Vec::new);
+            let repeated = candidates
+                .iter()
+                .find(|g| history[**g - first_generation] == self.current.data);
+            if let Some(start) = repeated {
+                return Cycle {
+                    start: *start,
+                    period: self.generation - start,
+                };
+            }
+            candidates.push(self.generation);
+            history.push(self.current.data.clone());
+        }
+    }
+}
+
+#[test]
+fn test_automaton() {
+    use super::BinaryCodec;
+
+    let life = |cell: u8, hood: &[u8]| {
+        let alive = hood.iter().filter(|v| **v > 0).count();
+        match (cell, alive) {
+            (1, 2) | (_, 3) => 1,
+            _ => 0,
+        }
+    };
+    let blinker: Grid<u8> =
+        Grid::from_lines_with(&[".....", "..#..", "..#..", "..#..", "....."], &BinaryCodec)
+            .unwrap();
+    let mut generations = vec![];
+    {
+        let mut automaton = Automaton::new(blinker.clone(), Neighborhood::Moore, life);
+        automaton.on_generation(|generation, grid| generations.push((generation, grid.clone())));
+        assert!(automaton.step());
+        assert_eq!(
+            automaton.grid().to_lines_with(&BinaryCodec),
+            vec![".....", ".....", ".###.", ".....", "....."]
+        );
+        assert_eq!(automaton.run(3), 3);
+        assert_eq!(automaton.generation(), 4);
+        assert_eq!(automaton.grid(), &blinker);
+        assert_eq!(automaton.run_until_cycle(), Cycle { start: 4, period: 2 });
+    }
+    assert_eq!(generations.len(), 6);
+    assert_eq!(generations[1], (2, blinker.clone()));
+
+    let block: Grid<u8> =
+        Grid::from_lines_with(&["....", ".##.", ".#..", "...."], &BinaryCodec).unwrap();
+    let mut automaton = Automaton::new(block, Neighborhood::Moore, life);
+    assert_eq!(automaton.run_until_stable(), 1);
+    assert!(!automaton.step());
+    assert_eq!(automaton.run(10), 0);
+    assert_eq!(automaton.run_until_cycle(), Cycle { start: 1, period: 1 });
+    assert_eq!(automaton.into_grid().data.iter().filter(|v| **v > 0).count(), 4);
+
+    let empty = Grid { rows: 3, cols: 0, data: vec![] };
+    assert!(!Automaton::new(empty, Neighborhood::Moore, life).step());
+
+    // pseudo random soups large enough for 3 and 4 threads, more threads than rows included
+    let soup = |rows: usize, cols: usize| Grid {
+        rows: rows,
+        cols: cols,
+        data: (0..rows * cols).map(|i| (i * 7919 % 13 < 5) as u8).collect::<Vec<u8>>(),
+    };
+    for (rows, cols) in [(97, 131), (2, 9000)].iter() {
+        let mut serial = Automaton::new(soup(*rows, *cols), Neighborhood::Moore, life);
+        serial.run(10);
+        for threads in [2, 3, 8].iter() {
+            let mut parallel = Automaton::new(soup(*rows, *cols), Neighborhood::Moore, life);
+            parallel.set_threads(*threads);
+            parallel.run(10);
+            assert_eq!(parallel.generation(), serial.generation());
+            assert_eq!(parallel.grid(), serial.grid());
+        }
+    }
+}
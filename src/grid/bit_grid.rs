@@ -0,0 +1,247 @@
+use super::{DataType, Grid, Orientation};
+
+const WORD_BITS: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edge {
+    Top,
+    Right,
+    Bottom,
+    Left,
+}
+
+/// Reverses the lowest len bits.
+pub fn reverse_bits(bits: u64, len: usize) -> u64 {
+    if len == 0 {
+        0
+    } else {
+        bits.reverse_bits() >> (WORD_BITS - len)
+    }
+}
+
+/// Boolean grid with each row packed into u64 words. Within a word the first column is the
+/// most significant bit, such that integers read like the rows, e.g., "#..#" is 0b1001.
+#[derive(Clone, PartialEq, Debug)]
+pub struct BitGrid {
+    pub rows: usize,
+    pub cols: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(rows: usize, cols: usize) -> BitGrid {
+        let words_per_row = cols.div_ceil(WORD_BITS);
+        BitGrid {
+            rows: rows,
+            cols: cols,
+            words_per_row: words_per_row,
+            words: vec![0; rows * words_per_row],
+        }
+    }
+
+    /// Non-zero cells are set.
+    pub fn from_grid<T: DataType>(grid: &Grid<T>) -> BitGrid {
+        let mut res = BitGrid::new(grid.rows, grid.cols);
+        for (r, c) in iproduct!(0..grid.rows, 0..grid.cols) {
+            res.set(r, c, *grid.at(r, c) != T::zero());
+        }
+        res
+    }
+
+    /// Set cells become 1, others 0.
+    pub fn to_grid<T: DataType>(&self) -> Grid<T> {
+        Grid {
+            rows: self.rows,
+            cols: self.cols,
+            data: iproduct!(0..self.rows, 0..self.cols)
+                .map(|(r, c)| if self.get(r, c) { T::one() } else { T::zero() })
+                .collect(),
+        }
+    }
+
+    fn position(&self, row: usize, col: usize) -> (usize, u32) {
+        if row >= self.rows || col >= self.cols {
+            panic!(
+                "Index ({}, {}) out of bounds for bit grid of shape {}x{}",
+                row, col, self.rows, self.cols
+            );
+        }
+        (
+            row * self.words_per_row + col / WORD_BITS,
+            (WORD_BITS - 1 - col % WORD_BITS) as u32,
+        )
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> bool {
+        let (word, bit) = self.position(row, col);
+        self.words[word] >> bit & 1 == 1
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: bool) {
+        let (word, bit) = self.position(row, col);
+        if value {
+            self.words[word] |= 1 << bit;
+        } else {
+            self.words[word] &= !(1 << bit);
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// The width <= 64 cells of the row starting at col as integer.
+    pub fn bits(&self, row: usize, col: usize, width: usize) -> u64 {
+        if width == 0 {
+            return 0;
+        }
+        if width > WORD_BITS || col + width > self.cols {
+            panic!("Cannot read {} bits from col {} of {} cols", width, col, self.cols);
+        }
+        let (word, offset) = (row * self.words_per_row + col / WORD_BITS, col % WORD_BITS);
+        let mut res = self.words[word] << offset;
+        if offset + width > WORD_BITS {
+            res |= self.words[word + 1] >> (WORD_BITS - offset);
+        }
+        res >> (WORD_BITS - width)
+    }
+
+    /// Sets the cells of the row starting at col where the lowest width bits are set.
+    pub fn or_bits(&mut self, row: usize, col: usize, width: usize, bits: u64) {
+        if width == 0 {
+            return;
+        }
+        if width > WORD_BITS || col + width > self.cols {
+            panic!("Cannot write {} bits to col {} of {} cols", width, col, self.cols);
+        }
+        let (word, offset) = (row * self.words_per_row + col / WORD_BITS, col % WORD_BITS);
+        let aligned = bits << (WORD_BITS - width);
+        self.words[word] |= aligned >> offset;
+        if offset + width > WORD_BITS {
+            self.words[word + 1] |= aligned << (WORD_BITS - offset);
+        }
+    }
+
+    /// Needs at most 64 columns.
+    pub fn row_bits(&self, row: usize) -> u64 {
+        self.bits(row, 0, self.cols)
+    }
+
+    /// Needs at most 64 rows, the top row is the most significant bit.
+    pub fn col_bits(&self, col: usize) -> u64 {
+        if self.rows > WORD_BITS {
+            panic!("Cannot read a column of {} rows as integer", self.rows);
+        }
+        (0..self.rows).fold(0, |acc, r| acc << 1 | self.get(r, col) as u64)
+    }
+
+    /// Rows are read from left to right, columns from top to bottom.
+    pub fn edge(&self, edge: Edge) -> u64 {
+        match edge {
+            Edge::Top => self.row_bits(0),
+            Edge::Right => self.col_bits(self.cols - 1),
+            Edge::Bottom => self.row_bits(self.rows - 1),
+            Edge::Left => self.col_bits(0),
+        }
+    }
+
+    pub fn orient(&self, orientation: Orientation) -> BitGrid {
+        let (rows, cols) = orientation.shape(self.rows, self.cols);
+        let mut res = BitGrid::new(rows, cols);
+        for (r, c) in iproduct!(0..rows, 0..cols) {
+            let (src_r, src_c) = orientation.source_index(r, c, self.rows, self.cols);
+            if self.get(src_r, src_c) {
+                res.set(r, c, true);
+            }
+        }
+        res
+    }
+
+    /// Cells set here but not in other, both need the same shape.
+    pub fn and_not(&self, other: &BitGrid) -> BitGrid {
+        if (self.rows, self.cols) != (other.rows, other.cols) {
+            panic!(
+                "Shapes {}x{} and {}x{} differ",
+                self.rows, self.cols, other.rows, other.cols
+            );
+        }
+        BitGrid {
+            rows: self.rows,
+            cols: self.cols,
+            words_per_row: self.words_per_row,
+            words: izip!(self.words.iter(), other.words.iter())
+                .map(|(a, b)| a & !b)
+                .collect(),
+        }
+    }
+
+    /// Upper left corners where all set cells of the template are set, the template needs
+    /// at most 64 columns.
+    pub fn find_pattern(&self, template: &BitGrid) -> Vec<(usize, usize)> {
+        if template.rows > self.rows || template.cols > self.cols {
+            return vec![];
+        }
+        let template_rows = (0..template.rows)
+            .map(|r| template.row_bits(r))
+            .collect::<Vec<u64>>();
+        iproduct!(
+            0..self.rows + 1 - template.rows,
+            0..self.cols + 1 - template.cols
+        )
+        .filter(|(row, col)| {
+            template_rows.iter().enumerate().all(|(r, t)| {
+                self.bits(row + r, *col, template.cols) & t == *t
+            })
+        })
+        .collect()
+    }
+
+    /// Sets all cells that are set in the template placed at (row, col).
+    pub fn or_pattern(&mut self, template: &BitGrid, row: usize, col: usize) {
+        for r in 0..template.rows {
+            self.or_bits(row + r, col, template.cols, template.row_bits(r));
+        }
+    }
+}
+
+#[test]
+fn test_bit_grid() {
+    use super::BinaryCodec;
+
+    let grid: Grid<u8> =
+        Grid::from_lines_with(&["#..#", "##..", "...#"], &BinaryCodec).unwrap();
+    let bits = BitGrid::from_grid(&grid);
+    assert_eq!(bits.count_ones(), 5);
+    assert_eq!(bits.row_bits(0), 0b1001);
+    assert_eq!(bits.col_bits(0), 0b110);
+    assert_eq!(bits.edge(Edge::Right), 0b101);
+    assert_eq!(bits.edge(Edge::Bottom), 0b0001);
+    assert_eq!(reverse_bits(0b0001, 4), 0b1000);
+    assert_eq!(bits.to_grid::<u8>(), grid);
+    for o in Orientation::ALL.iter() {
+        assert_eq!(bits.orient(*o).to_grid::<u8>(), grid.orient(*o));
+    }
+
+    // rows spanning several words
+    let mut wide = BitGrid::new(2, 150);
+    wide.set(1, 63, true);
+    wide.set(1, 64, true);
+    wide.set(1, 149, true);
+    assert_eq!(wide.count_ones(), 3);
+    assert_eq!(wide.bits(1, 62, 4), 0b0110);
+    assert_eq!(wide.bits(1, 100, 50), 1);
+    wide.or_bits(0, 60, 10, 0b1000000001);
+    assert!(wide.get(0, 60) && wide.get(0, 69) && !wide.get(0, 64));
+    assert_eq!(wide.and_not(&wide).count_ones(), 0);
+
+    let mut sea = BitGrid::new(3, 70);
+    let monster = BitGrid::from_grid(&grid);
+    sea.or_pattern(&monster, 0, 62);
+    sea.set(2, 0, true);
+    assert_eq!(sea.find_pattern(&monster), vec![(0, 62)]);
+    assert_eq!(sea.and_not(&sea.clone()).count_ones(), 0);
+    let mut found = BitGrid::new(3, 70);
+    found.or_pattern(&monster, 0, 62);
+    assert_eq!(sea.and_not(&found).count_ones(), 1);
+}
@@ -0,0 +1,106 @@
+use super::DataType;
+use std::fmt;
+
+/// Maps the characters of a puzzle input to cell values and back.
+pub trait CellCodec<T: DataType> {
+    fn decode(&self, c: char) -> Option<T>;
+    fn encode(&self, value: T) -> Option<char>;
+}
+
+/// Rendered for values a codec cannot encode.
+pub const UNKNOWN_CELL: char = '?';
+
+/// '.' is floor, 'L' an empty and '#' an occupied seat.
+#[derive(Clone, Copy, Debug)]
+pub struct SeatCodec;
+impl<T: DataType> CellCodec<T> for SeatCodec {
+    fn decode(&self, c: char) -> Option<T> {
+        match c {
+            '.' => Some(T::zero()),
+            'L' => Some(T::one()),
+            '#' => Some(T::one() + T::one()),
+            _ => None,
+        }
+    }
+    fn encode(&self, value: T) -> Option<char> {
+        if value == T::zero() {
+            Some('.')
+        } else if value == T::one() {
+            Some('L')
+        } else if value == T::one() + T::one() {
+            Some('#')
+        } else {
+            None
+        }
+    }
+}
+
+/// '.' is 0 and '#' is 1.
+#[derive(Clone, Copy, Debug)]
+pub struct BinaryCodec;
+impl<T: DataType> CellCodec<T> for BinaryCodec {
+    fn decode(&self, c: char) -> Option<T> {
+        match c {
+            '.' => Some(T::zero()),
+            '#' => Some(T::one()),
+            _ => None,
+        }
+    }
+    fn encode(&self, value: T) -> Option<char> {
+        if value == T::zero() {
+            Some('.')
+        } else if value == T::one() {
+            Some('#')
+        } else {
+            None
+        }
+    }
+}
+
+/// Arbitrary alphabets, e.g., `CharMap::new(&[('.', 0), ('#', 1), ('O', 2)])`.
+#[derive(Clone, Debug)]
+pub struct CharMap<T: DataType> {
+    pairs: Vec<(char, T)>,
+}
+impl<T: DataType> CharMap<T> {
+    pub fn new(pairs: &[(char, T)]) -> CharMap<T> {
+        CharMap {
+            pairs: pairs.to_vec(),
+        }
+    }
+}
+impl<T: DataType> CellCodec<T> for CharMap<T> {
+    fn decode(&self, c: char) -> Option<T> {
+        self.pairs.iter().find(|(pc, _)| *pc == c).map(|(_, v)| *v)
+    }
+    fn encode(&self, value: T) -> Option<char> {
+        self.pairs.iter().find(|(_, v)| *v == value).map(|(c, _)| *c)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridParseError {
+    Empty,
+    UnknownChar { row: usize, col: usize, c: char },
+    RaggedRow { row: usize, expected: usize, found: usize },
+}
+
+impl fmt::Display for GridParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridParseError::Empty => write!(f, "Grid has no rows"),
+            GridParseError::UnknownChar { row, col, c } => {
+                write!(f, "Unknown character '{}' at row {}, col {}", c, row, col)
+            }
+            GridParseError::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Row {} has {} columns but {} were expected",
+                row, found, expected
+            ),
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use super::{Bounds, DataType, Grid, Neighborhood};
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ComponentStats {
+    pub label: u32,
+    pub size: usize,
+    pub bounds: Bounds,
+    /// mean (row, col) of the cells
+    pub centroid: (f64, f64),
+}
+
+/// Breadth first search from start over the cells for which the predicate holds. The
+/// labels are set to label for all reached cells, which are returned in visiting order.
+fn fill<T: DataType, P: Fn(&T) -> bool>(
+    grid: &Grid<T>,
+    start: (usize, usize),
+    hood: Neighborhood,
+    predicate: &P,
+    labels: &mut Grid<u32>,
+    label: u32,
+) -> Vec<(usize, usize)> {
+    let mut res = vec![];
+    if labels[start.0][start.1] != 0 || !predicate(grid.at(start.0, start.1)) {
+        return res;
+    }
+    labels[start.0][start.1] = label;
+    let mut queue = VecDeque::from(vec![start]);
+    while let Some((row, col)) = queue.pop_front() {
+        res.push((row, col));
+        for (r, c, value) in grid.neighbors(row, col, hood) {
+            let (r, c) = (r as usize, c as usize);
+            if labels[r][c] == 0 && predicate(value) {
+                labels[r][c] = label;
+                queue.push_back((r, c));
+            }
+        }
+    }
+    res
+}
+
+impl<T: DataType> Grid<T> {
+    /// 4-connected region around start where the predicate holds, empty if it does not
+    /// hold at start.
+    pub fn flood_fill<P: Fn(&T) -> bool>(
+        &self,
+        start: (usize, usize),
+        predicate: P,
+    ) -> Vec<(usize, usize)> {
+        let mut labels = Grid {
+            rows: self.rows,
+            cols: self.cols,
+            data: vec![0u32; self.data.len()],
+        };
+        fill(self, start, Neighborhood::VonNeumann, &predicate, &mut labels, 1)
+    }
+
+    /// Labels connected cells where the predicate holds with 1, 2, ... in row-major order
+    /// of their first cell, all other cells are 0. Use `Neighborhood::VonNeumann` for
+    /// 4-connectivity and `Neighborhood::Moore` for 8-connectivity.
+    pub fn label_components<P: Fn(&T) -> bool>(
+        &self,
+        hood: Neighborhood,
+        predicate: P,
+    ) -> (Grid<u32>, u32) {
+        let mut labels = Grid {
+            rows: self.rows,
+            cols: self.cols,
+            data: vec![0u32; self.data.len()],
+        };
+        let mut n_labels = 0;
+        for (r, c) in iproduct!(0..self.rows, 0..self.cols) {
+            if !fill(self, (r, c), hood, &predicate, &mut labels, n_labels + 1).is_empty() {
+                n_labels += 1;
+            }
+        }
+        (labels, n_labels)
+    }
+}
+
+/// Statistics of all non-zero labels ordered by label.
+pub fn component_stats(labels: &Grid<u32>) -> Vec<ComponentStats> {
+    let n_labels = labels.data.iter().max().cloned().unwrap_or(0) as usize;
+    let mut sums = vec![(0usize, 0usize, 0usize); n_labels];
+    let mut bounds: Vec<Option<Bounds>> = vec![None; n_labels];
+    for (r, c) in iproduct!(0..labels.rows, 0..labels.cols) {
+        let label = labels[r][c] as usize;
+        if label == 0 {
+            continue;
+        }
+        let (size, row_sum, col_sum) = sums[label - 1];
+        sums[label - 1] = (size + 1, row_sum + r, col_sum + c);
+        let (r, c) = (r as isize, c as isize);
+        bounds[label - 1] = Some(match bounds[label - 1] {
+            Some(b) => Bounds {
+                row_min: b.row_min.min(r),
+                row_max: b.row_max.max(r),
+                col_min: b.col_min.min(c),
+                col_max: b.col_max.max(c),
+            },
+            None => Bounds {
+                row_min: r,
+                row_max: r,
+                col_min: c,
+                col_max: c,
+            },
+        });
+    }
+    izip!(sums.iter(), bounds.iter())
+        .enumerate()
+        // labels without cells are skipped
+        .filter_map(|(i, ((size, row_sum, col_sum), b))| {
+            Some(ComponentStats {
+                label: i as u32 + 1,
+                size: *size,
+                bounds: (*b)?,
+                centroid: (
+                    *row_sum as f64 / *size as f64,
+                    *col_sum as f64 / *size as f64,
+                ),
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_components() {
+    use super::BinaryCodec;
+
+    let grid: Grid<u8> = Grid::from_lines_with(
+        &["##..#", "#...#", "..#..", "...##"],
+        &BinaryCodec,
+    )
+    .unwrap();
+    let filled = grid.flood_fill((0, 0), |v| *v == 1);
+    assert_eq!(filled, vec![(0, 0), (0, 1), (1, 0)]);
+    assert_eq!(grid.flood_fill((0, 2), |v| *v == 1), vec![]);
+    assert_eq!(grid.flood_fill((0, 2), |v| *v == 0).len(), 12);
+
+    let (labels, n) = grid.label_components(Neighborhood::VonNeumann, |v| *v == 1);
+    assert_eq!(n, 4);
+    assert_eq!(
+        labels.data,
+        vec![1, 1, 0, 0, 2, 1, 0, 0, 0, 2, 0, 0, 3, 0, 0, 0, 0, 0, 4, 4]
+    );
+    let (labels_8, n_8) = grid.label_components(Neighborhood::Moore, |v| *v == 1);
+    // (2, 2) touches (3, 3) diagonally
+    assert_eq!(n_8, 3);
+    assert_eq!(labels_8[3][4], 3);
+
+    let stats = component_stats(&labels);
+    assert_eq!(stats.len(), 4);
+    assert_eq!(stats[0].size, 3);
+    assert_eq!((stats[0].bounds.rows(), stats[0].bounds.cols()), (2, 2));
+    assert_eq!(stats[3].centroid, (3.0, 3.5));
+    let stats_8 = component_stats(&labels_8);
+    assert_eq!(stats_8[2].size, 3);
+    assert_eq!(stats_8[2].bounds.row_min, 2);
+}
@@ -0,0 +1,294 @@
+use super::{DataType, Grid, GridView, IdxTransform};
+use std::ops::{Index, IndexMut};
+
+/// D-dimensional grid in row-major order, i.e., the last axis is contiguous. The data is
+/// kept as 2D grid with the last axis as columns, such that 2D slices are `GridView`s with
+/// a strided transformation.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GridN<T: DataType, const D: usize> {
+    shape: [usize; D],
+    strides: [usize; D],
+    flat: Grid<T>,
+}
+
+/// Maps the cells of a 2D slice to the flat data of a `GridN`.
+#[derive(Clone, Copy, Debug)]
+pub struct Strided {
+    rows: usize,
+    cols: usize,
+    offset: usize,
+    row_stride: usize,
+    col_stride: usize,
+    flat_cols: usize,
+}
+impl IdxTransform for Strided {
+    fn apply(&self, row: usize, col: usize) -> (usize, usize) {
+        let flat_index = self.offset + row * self.row_stride + col * self.col_stride;
+        (flat_index / self.flat_cols, flat_index % self.flat_cols)
+    }
+    fn tf_rows(&self) -> usize {
+        self.rows
+    }
+    fn tf_cols(&self) -> usize {
+        self.cols
+    }
+    fn make(rows: usize, cols: usize) -> Self {
+        Strided {
+            rows: rows,
+            cols: cols,
+            offset: 0,
+            row_stride: cols,
+            col_stride: 1,
+            flat_cols: cols.max(1),
+        }
+    }
+}
+
+fn strides_of<const D: usize>(shape: &[usize; D]) -> [usize; D] {
+    let mut strides = [1; D];
+    for axis in (0..D.saturating_sub(1)).rev() {
+        strides[axis] = strides[axis + 1] * shape[axis + 1];
+    }
+    strides
+}
+
+/// All offsets in {-1, 0, 1}^D except the origin.
+pub fn moore_offsets_n<const D: usize>() -> Vec<[isize; D]> {
+    let n = 3usize.pow(D as u32);
+    (0..n)
+        .map(|i| {
+            let mut offset = [0isize; D];
+            let mut rest = i;
+            for axis in (0..D).rev() {
+                offset[axis] = (rest % 3) as isize - 1;
+                rest /= 3;
+            }
+            offset
+        })
+        .filter(|offset| offset.iter().any(|o| *o != 0))
+        .collect()
+}
+
+impl<T: DataType, const D: usize> GridN<T, D> {
+    pub fn new(shape: [usize; D], value: T) -> GridN<T, D> {
+        if D == 0 {
+            panic!("GridN needs at least one dimension");
+        }
+        let cols = shape[D - 1];
+        let rows = shape[..D - 1].iter().product();
+        GridN {
+            shape: shape,
+            strides: strides_of(&shape),
+            flat: Grid {
+                rows: rows,
+                cols: cols,
+                data: vec![value; rows * cols],
+            },
+        }
+    }
+
+    /// Embeds the 2D grid as the last two axes, all other axes have length 1.
+    pub fn from_grid(grid: &Grid<T>) -> GridN<T, D> {
+        if D < 2 {
+            panic!("Cannot embed a 2D grid into {} dimensions", D);
+        }
+        let mut shape = [1; D];
+        shape[D - 2] = grid.rows;
+        shape[D - 1] = grid.cols;
+        let mut res = GridN::new(shape, T::zero());
+        res.flat.data.copy_from_slice(&grid.data);
+        res
+    }
+
+    pub fn shape(&self) -> [usize; D] {
+        self.shape
+    }
+    pub fn strides(&self) -> [usize; D] {
+        self.strides
+    }
+    pub fn len(&self) -> usize {
+        self.flat.data.len()
+    }
+    /// True if any dimension is 0.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn data(&self) -> &[T] {
+        &self.flat.data
+    }
+
+    pub fn contains(&self, idx: &[isize; D]) -> bool {
+        izip!(idx.iter(), self.shape.iter()).all(|(i, s)| *i >= 0 && (*i as usize) < *s)
+    }
+
+    pub fn flat_index(&self, idx: &[usize; D]) -> usize {
+        if izip!(idx.iter(), self.shape.iter()).any(|(i, s)| i >= s) {
+            panic!("Index {:?} out of bounds for shape {:?}", idx, self.shape);
+        }
+        izip!(idx.iter(), self.strides.iter())
+            .map(|(i, s)| i * s)
+            .sum()
+    }
+
+    pub fn unravel(&self, flat_index: usize) -> [usize; D] {
+        let mut idx = [0; D];
+        for axis in 0..D {
+            idx[axis] = flat_index / self.strides[axis] % self.shape[axis];
+        }
+        idx
+    }
+
+    pub fn at(&self, idx: &[usize; D]) -> &T {
+        &self.flat.data[self.flat_index(idx)]
+    }
+    pub fn at_mut(&mut self, idx: &[usize; D]) -> &mut T {
+        let flat_index = self.flat_index(idx);
+        &mut self.flat.data[flat_index]
+    }
+
+    /// All indices in row-major order together with the values.
+    pub fn indexed_iter<'a>(&'a self) -> impl Iterator<Item = ([usize; D], &'a T)> + 'a {
+        self.flat
+            .data
+            .iter()
+            .enumerate()
+            .map(move |(i, v)| (self.unravel(i), v))
+    }
+
+    /// Walks along the axis through the given index, the index component of the axis is
+    /// ignored.
+    pub fn lane<'a>(&'a self, axis: usize, idx: [usize; D]) -> impl Iterator<Item = &'a T> + 'a {
+        (0..self.shape[axis]).map(move |i| {
+            let mut idx = idx;
+            idx[axis] = i;
+            self.at(&idx)
+        })
+    }
+
+    /// 2D slice through the index with the given axes as rows and cols, the index
+    /// components of these two axes are ignored.
+    pub fn slice2d(
+        &self,
+        row_axis: usize,
+        col_axis: usize,
+        idx: [usize; D],
+    ) -> GridView<'_, T, Strided> {
+        if row_axis >= D || col_axis >= D || row_axis == col_axis {
+            panic!(
+                "Cannot slice axes {} and {} of a {}-dimensional grid",
+                row_axis, col_axis, D
+            );
+        }
+        let mut idx = idx;
+        idx[row_axis] = 0;
+        idx[col_axis] = 0;
+        let fixed = (0..D).filter(|axis| *axis != row_axis && *axis != col_axis);
+        if let Some(axis) = fixed.clone().find(|axis| idx[*axis] >= self.shape[*axis]) {
+            panic!(
+                "Index {:?} out of bounds for shape {:?} in axis {}",
+                idx, self.shape, axis
+            );
+        }
+        GridView {
+            row_start: 0,
+            row_end: self.flat.rows,
+            col_start: 0,
+            col_end: self.flat.cols,
+            grid: &self.flat,
+            tf: Strided {
+                rows: self.shape[row_axis],
+                cols: self.shape[col_axis],
+                offset: fixed.map(|axis| idx[axis] * self.strides[axis]).sum(),
+                row_stride: self.strides[row_axis],
+                col_stride: self.strides[col_axis],
+                flat_cols: self.flat.cols.max(1),
+            },
+        }
+    }
+
+    /// Neighbors with the given offsets in the order of the offsets, cells outside of the
+    /// grid are skipped.
+    pub fn neighbors<'a>(
+        &'a self,
+        idx: [usize; D],
+        offsets: &'a [[isize; D]],
+    ) -> impl Iterator<Item = ([usize; D], &'a T)> + 'a {
+        offsets.iter().filter_map(move |offset| {
+            let mut neighbor = [0isize; D];
+            for axis in 0..D {
+                neighbor[axis] = idx[axis] as isize + offset[axis];
+            }
+            if !self.contains(&neighbor) {
+                return None;
+            }
+            let mut neighbor_idx = [0usize; D];
+            for axis in 0..D {
+                neighbor_idx[axis] = neighbor[axis] as usize;
+            }
+            Some((neighbor_idx, self.at(&neighbor_idx)))
+        })
+    }
+}
+
+impl<T: DataType, const D: usize> Index<[usize; D]> for GridN<T, D> {
+    type Output = T;
+    fn index(&self, idx: [usize; D]) -> &Self::Output {
+        self.at(&idx)
+    }
+}
+impl<T: DataType, const D: usize> IndexMut<[usize; D]> for GridN<T, D> {
+    fn index_mut(&mut self, idx: [usize; D]) -> &mut Self::Output {
+        self.at_mut(&idx)
+    }
+}
+
+#[test]
+fn test_grid_n() {
+    let mut grid = GridN::<i32, 3>::new([2, 3, 4], 0);
+    for i in 0..grid.len() {
+        let idx = grid.unravel(i);
+        grid[idx] = i as i32;
+    }
+    assert_eq!(grid.strides(), [12, 4, 1]);
+    assert!(!grid.is_empty());
+    assert!(GridN::<i32, 3>::new([2, 0, 4], 0).is_empty());
+    assert_eq!(grid[[1, 2, 3]], 23);
+    assert_eq!(grid[[1, 0, 2]], 14);
+    assert_eq!(grid.unravel(14), [1, 0, 2]);
+    assert_eq!(grid.indexed_iter().nth(5), Some(([0, 1, 1], &5)));
+
+    assert_eq!(grid.lane(0, [0, 1, 1]).cloned().collect::<Vec<i32>>(), vec![5, 17]);
+    assert_eq!(grid.lane(1, [1, 0, 0]).cloned().collect::<Vec<i32>>(), vec![12, 16, 20]);
+
+    let slice = grid.slice2d(1, 2, [1, 0, 0]);
+    assert_eq!((slice.rows(), slice.cols()), (3, 4));
+    assert_eq!(*slice.at(1, 2), 18);
+    assert_eq!(slice.to_grid().data, (12..24).collect::<Vec<i32>>());
+    // the index components of the sliced axes do not matter
+    let slice = grid.slice2d(0, 2, [0, 2, 3]);
+    assert_eq!((slice.rows(), slice.cols()), (2, 4));
+    assert_eq!(slice.to_grid().data, vec![8, 9, 10, 11, 20, 21, 22, 23]);
+    let transposed = grid.slice2d(1, 0, [0, 0, 3]);
+    assert_eq!(transposed.to_grid().data, vec![3, 15, 7, 19, 11, 23]);
+    assert_eq!(transposed.wrapping(super::WrapAxes::Both).at(4, 3), &19);
+    assert!(std::panic::catch_unwind(|| grid.slice2d(1, 1, [0, 0, 0])).is_err());
+    assert!(std::panic::catch_unwind(|| grid.slice2d(0, 1, [0, 0, 4])).is_err());
+
+    let offsets = moore_offsets_n::<3>();
+    assert_eq!(offsets.len(), 26);
+    assert_eq!(moore_offsets_n::<4>().len(), 80);
+    assert_eq!(grid.neighbors([0, 0, 0], &offsets).count(), 7);
+    assert_eq!(grid.neighbors([1, 1, 1], &offsets).count(), 17);
+    let sum: i32 = grid.neighbors([0, 0, 0], &offsets).map(|(_, v)| *v).sum();
+    assert_eq!(sum, 1 + 4 + 5 + 12 + 13 + 16 + 17);
+
+    let plane = Grid {
+        rows: 2,
+        cols: 2,
+        data: vec![1, 0, 0, 1],
+    };
+    let cube = GridN::<i32, 4>::from_grid(&plane);
+    assert_eq!(cube.shape(), [1, 1, 2, 2]);
+    assert_eq!(cube[[0, 0, 1, 1]], 1);
+    assert_eq!(cube.slice2d(2, 3, [0, 0, 0, 0]).to_grid(), plane);
+}
@@ -0,0 +1,151 @@
+// Binary Netpbm export, see http://netpbm.sourceforge.net/doc/pgm.html and ppm.html.
+use super::{DataType, Grid};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// If set to a directory, solvers that support it write their grids as images there.
+pub const IMAGES_ENV_VAR: &str = "AOC_IMAGES";
+
+impl<T: DataType> Grid<T> {
+    /// Only the first channels of each pixel are written.
+    fn netpbm<F: Fn(T) -> [u8; 3]>(
+        &self,
+        magic: &str,
+        channels: usize,
+        scale: usize,
+        pixel: F,
+    ) -> Vec<u8> {
+        let (width, height) = (self.cols * scale, self.rows * scale);
+        let mut res = format!("{}\n{} {}\n255\n", magic, width, height).into_bytes();
+        res.reserve(width * height * channels);
+        for r in 0..self.rows {
+            let row_start = res.len();
+            for c in 0..self.cols {
+                let value = pixel(*self.at(r, c));
+                for _ in 0..scale {
+                    res.extend_from_slice(&value[..channels]);
+                }
+            }
+            for _ in 1..scale {
+                res.extend_from_within(row_start..row_start + width * channels);
+            }
+        }
+        res
+    }
+
+    /// Grayscale image with scale x scale pixels per cell.
+    pub fn to_pgm<F: Fn(T) -> u8>(&self, palette: F, scale: usize) -> Vec<u8> {
+        self.netpbm("P5", 1, scale, |v| [palette(v); 3])
+    }
+
+    /// RGB image with scale x scale pixels per cell.
+    pub fn to_ppm<F: Fn(T) -> [u8; 3]>(&self, palette: F, scale: usize) -> Vec<u8> {
+        self.netpbm("P6", 3, scale, palette)
+    }
+
+    pub fn write_pgm<P: AsRef<Path>, F: Fn(T) -> u8>(
+        &self,
+        path: P,
+        palette: F,
+        scale: usize,
+    ) -> io::Result<()> {
+        fs::write(path, self.to_pgm(palette, scale))
+    }
+
+    pub fn write_ppm<P: AsRef<Path>, F: Fn(T) -> [u8; 3]>(
+        &self,
+        path: P,
+        palette: F,
+        scale: usize,
+    ) -> io::Result<()> {
+        fs::write(path, self.to_ppm(palette, scale))
+    }
+}
+
+/// Writes numbered images <dir>/<prefix>_0001.ppm, <prefix>_0002.ppm, ...
+pub struct FrameWriter {
+    dir: PathBuf,
+    prefix: String,
+    scale: usize,
+    next: usize,
+}
+
+impl FrameWriter {
+    pub fn new<P: AsRef<Path>>(dir: P, prefix: &str, scale: usize) -> io::Result<FrameWriter> {
+        fs::create_dir_all(&dir)?;
+        Ok(FrameWriter {
+            dir: dir.as_ref().to_path_buf(),
+            prefix: prefix.to_string(),
+            scale: scale,
+            next: 1,
+        })
+    }
+
+    /// Writer into the directory of `IMAGES_ENV_VAR`, None if the variable is not set.
+    pub fn from_env(prefix: &str, scale: usize) -> Option<FrameWriter> {
+        let dir = env::var(IMAGES_ENV_VAR).ok()?;
+        match FrameWriter::new(&dir, prefix, scale) {
+            Ok(writer) => Some(writer),
+            Err(e) => {
+                error!("Could not create image directory '{}': {}", dir, e);
+                None
+            }
+        }
+    }
+
+    pub fn write_frame<T: DataType, F: Fn(T) -> [u8; 3]>(
+        &mut self,
+        grid: &Grid<T>,
+        palette: F,
+    ) -> io::Result<PathBuf> {
+        let path = self
+            .dir
+            .join(format!("{}_{:04}.ppm", self.prefix, self.next));
+        grid.write_ppm(&path, palette, self.scale)?;
+        self.next += 1;
+        Ok(path)
+    }
+}
+
+#[test]
+fn test_image() {
+    let grid = Grid {
+        rows: 2,
+        cols: 3,
+        data: vec![0u8, 1, 2, 2, 1, 0],
+    };
+    let image = |header: &[u8], pixels: &[u8]| [header, pixels].concat();
+    let gray = |v: u8| v * 100;
+    assert_eq!(
+        grid.to_pgm(gray, 1),
+        image(b"P5\n3 2\n255\n", &[0, 100, 200, 200, 100, 0])
+    );
+    let (top, bottom) = ([0, 0, 100, 100, 200, 200], [200, 200, 100, 100, 0, 0]);
+    assert_eq!(
+        grid.to_pgm(gray, 2),
+        image(b"P5\n6 4\n255\n", &[top, top, bottom, bottom].concat())
+    );
+    let rgb = |v: u8| [v, 0, 255 - v];
+    assert_eq!(
+        grid.to_ppm(rgb, 1),
+        image(
+            b"P6\n3 2\n255\n",
+            &[0, 0, 255, 1, 0, 254, 2, 0, 253, 2, 0, 253, 1, 0, 254, 0, 0, 255]
+        )
+    );
+    assert_eq!(
+        grid.to_ppm(rgb, 3).len(),
+        b"P6\n9 6\n255\n".len() + 9 * 6 * 3
+    );
+
+    let dir = env::temp_dir().join(format!("aoc2020_frames_{}", std::process::id()));
+    let mut writer = FrameWriter::new(&dir, "frame", 1).unwrap();
+    let first = writer.write_frame(&grid, rgb).unwrap();
+    let second = writer.write_frame(&grid.rot90(), rgb).unwrap();
+    assert_eq!(first.file_name().unwrap(), "frame_0001.ppm");
+    assert_eq!(second.file_name().unwrap(), "frame_0002.ppm");
+    assert!(fs::read(&second).unwrap().starts_with(b"P6\n2 3\n"));
+    fs::remove_dir_all(&dir).unwrap();
+}
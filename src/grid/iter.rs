@@ -0,0 +1,215 @@
+use super::{AxisIterator, DataType, Grid, GridView, IdxTransform, Identity};
+use std::ops::Range;
+
+/// Cells on a line through a view, e.g., a diagonal.
+#[derive(Clone, Copy)]
+pub struct LineIterator<'a, T: DataType, TF: IdxTransform> {
+    view: GridView<'a, T, TF>,
+    row: isize,
+    col: isize,
+    direction: (isize, isize),
+    remaining: usize,
+}
+
+impl<'a, T: DataType, TF: IdxTransform> LineIterator<'a, T, TF> {
+    fn get(&self, steps: usize) -> &'a T {
+        let steps = steps as isize;
+        self.view.at(
+            (self.row + steps * self.direction.0) as usize,
+            (self.col + steps * self.direction.1) as usize,
+        )
+    }
+}
+
+impl<'a, T: DataType, TF: IdxTransform> Iterator for LineIterator<'a, T, TF> {
+    type Item = &'a T;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let res = self.get(0);
+        self.row += self.direction.0;
+        self.col += self.direction.1;
+        self.remaining -= 1;
+        Some(res)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<'a, T: DataType, TF: IdxTransform> DoubleEndedIterator for LineIterator<'a, T, TF> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(self.get(self.remaining))
+    }
+}
+impl<'a, T: DataType, TF: IdxTransform> ExactSizeIterator for LineIterator<'a, T, TF> {}
+
+/// Iterators over the rows or cols of a view.
+pub struct AxesIterator<'a, T: DataType, TF: IdxTransform> {
+    view: GridView<'a, T, TF>,
+    indices: Range<usize>,
+    rows: bool,
+}
+
+impl<'a, T: DataType, TF: IdxTransform + Copy> AxesIterator<'a, T, TF> {
+    fn make(&self, idx: usize) -> AxisIterator<'a, T, TF> {
+        if self.rows {
+            AxisIterator::make_row_view(idx, self.view, 1)
+        } else {
+            AxisIterator::make_col_view(idx, self.view, 1)
+        }
+    }
+}
+
+impl<'a, T: DataType, TF: IdxTransform + Copy> Iterator for AxesIterator<'a, T, TF> {
+    type Item = AxisIterator<'a, T, TF>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.indices.next()?;
+        Some(self.make(idx))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+impl<'a, T: DataType, TF: IdxTransform + Copy> DoubleEndedIterator for AxesIterator<'a, T, TF> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let idx = self.indices.next_back()?;
+        Some(self.make(idx))
+    }
+}
+impl<'a, T: DataType, TF: IdxTransform + Copy> ExactSizeIterator for AxesIterator<'a, T, TF> {}
+
+impl<'a, T: DataType, TF: IdxTransform + Copy> GridView<'a, T, TF> {
+    /// Cells with col - row == offset from top left to bottom right, 0 is the main
+    /// diagonal.
+    pub fn diagonal(&self, offset: isize) -> LineIterator<'a, T, TF> {
+        let (row, col) = if offset >= 0 { (0, offset) } else { (-offset, 0) };
+        let remaining = (self.rows() as isize - row)
+            .min(self.cols() as isize - col)
+            .max(0) as usize;
+        LineIterator {
+            view: *self,
+            row: row,
+            col: col,
+            direction: (1, 1),
+            remaining: remaining,
+        }
+    }
+
+    /// Cells with row + col == sum from top right to bottom left.
+    pub fn anti_diagonal(&self, sum: usize) -> LineIterator<'a, T, TF> {
+        let (rows, cols) = (self.rows() as isize, self.cols() as isize);
+        let sum = sum as isize;
+        let (row, col) = (0.max(sum - cols + 1), sum.min(cols - 1));
+        LineIterator {
+            view: *self,
+            row: row,
+            col: col,
+            direction: (1, -1),
+            remaining: (col + 1).min(rows - row).max(0) as usize,
+        }
+    }
+
+    /// Named iter_rows, since `rows` is the number of rows.
+    pub fn iter_rows(&self) -> AxesIterator<'a, T, TF> {
+        AxesIterator {
+            view: *self,
+            indices: 0..self.rows(),
+            rows: true,
+        }
+    }
+
+    pub fn iter_cols(&self) -> AxesIterator<'a, T, TF> {
+        AxesIterator {
+            view: *self,
+            indices: 0..self.cols(),
+            rows: false,
+        }
+    }
+
+    /// Cells in row-major order with their (row, col) in the view.
+    pub fn indexed_iter(
+        &self,
+    ) -> impl ExactSizeIterator<Item = ((usize, usize), &'a T)> + 'a
+    where
+        TF: 'a,
+    {
+        let (view, cols) = (*self, self.cols());
+        (0..self.rows() * cols).map(move |i| ((i / cols, i % cols), view.at(i / cols, i % cols)))
+    }
+}
+
+impl<'a, T: DataType> Grid<T> {
+    pub fn diagonal(&'a self, offset: isize) -> LineIterator<'a, T, Identity> {
+        self.as_view().diagonal(offset)
+    }
+    pub fn anti_diagonal(&'a self, sum: usize) -> LineIterator<'a, T, Identity> {
+        self.as_view().anti_diagonal(sum)
+    }
+    pub fn iter_rows(&'a self) -> AxesIterator<'a, T, Identity> {
+        self.as_view().iter_rows()
+    }
+    pub fn iter_cols(&'a self) -> AxesIterator<'a, T, Identity> {
+        self.as_view().iter_cols()
+    }
+    pub fn indexed_iter(&'a self) -> impl ExactSizeIterator<Item = ((usize, usize), &'a T)> {
+        let cols = self.cols;
+        self.data
+            .iter()
+            .enumerate()
+            .map(move |(i, v)| ((i / cols, i % cols), v))
+    }
+}
+
+#[test]
+fn test_iter() {
+    use super::Rot90;
+
+    let grid = Grid {
+        rows: 3,
+        cols: 4,
+        data: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+    };
+    let values = |it: LineIterator<i32, Identity>| it.cloned().collect::<Vec<i32>>();
+    assert_eq!(values(grid.diagonal(0)), vec![0, 5, 10]);
+    assert_eq!(values(grid.diagonal(2)), vec![2, 7]);
+    assert_eq!(values(grid.diagonal(-2)), vec![8]);
+    assert_eq!(values(grid.diagonal(4)), vec![]);
+    assert_eq!(values(grid.diagonal(-3)), vec![]);
+    assert_eq!(values(grid.anti_diagonal(0)), vec![0]);
+    assert_eq!(values(grid.anti_diagonal(3)), vec![3, 6, 9]);
+    assert_eq!(values(grid.anti_diagonal(4)), vec![7, 10]);
+    assert_eq!(values(grid.anti_diagonal(5)), vec![11]);
+    assert_eq!(values(grid.anti_diagonal(6)), vec![]);
+    assert_eq!(grid.diagonal(1).len(), 3);
+    assert_eq!(grid.anti_diagonal(2).rev().cloned().collect::<Vec<i32>>(), vec![8, 5, 2]);
+
+    // every cell lies on exactly one diagonal and one anti-diagonal
+    let diagonals: usize = (-2..4).map(|k| grid.diagonal(k).len()).sum();
+    let anti_diagonals: usize = (0..6).map(|k| grid.anti_diagonal(k).len()).sum();
+    assert_eq!((diagonals, anti_diagonals), (12, 12));
+
+    let rows = grid
+        .iter_rows()
+        .map(|row| row.cloned().collect::<Vec<i32>>())
+        .collect::<Vec<_>>();
+    assert_eq!(rows, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]]);
+    assert_eq!(grid.iter_cols().len(), 4);
+    let last_col = grid.iter_cols().next_back().unwrap();
+    assert_eq!(last_col.cloned().collect::<Vec<i32>>(), vec![3, 7, 11]);
+
+    let rotated = grid.transformed_view::<Rot90>(1..3, 0..4);
+    assert_eq!(rotated.diagonal(0).cloned().collect::<Vec<i32>>(), vec![7, 10]);
+    let indexed = rotated.indexed_iter().collect::<Vec<_>>();
+    assert_eq!(indexed.len(), 8);
+    assert_eq!(indexed[1], ((0, 1), &11));
+    assert_eq!(grid.indexed_iter().nth(6), Some(((1, 2), &6)));
+    assert!(grid
+        .indexed_iter()
+        .all(|((r, c), v)| grid.at(r, c) == v));
+}
+
@@ -0,0 +1,139 @@
+use super::{DataType, Grid};
+
+pub const MOORE_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+pub const VON_NEUMANN_OFFSETS: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets (row, col) relative to the center cell.
+#[derive(Clone, Copy, Debug)]
+pub enum Neighborhood<'a> {
+    /// 8 cells including the diagonals
+    Moore,
+    /// 4 cells excluding the diagonals
+    VonNeumann,
+    Custom(&'a [(isize, isize)]),
+}
+
+impl<'a> Neighborhood<'a> {
+    pub fn offsets(&self) -> &'a [(isize, isize)] {
+        match self {
+            Neighborhood::Moore => &MOORE_OFFSETS,
+            Neighborhood::VonNeumann => &VON_NEUMANN_OFFSETS,
+            Neighborhood::Custom(offsets) => offsets,
+        }
+    }
+}
+
+/// What neighbors outside of the grid look like.
+#[derive(Clone, Copy, Debug)]
+pub enum Boundary<'a, T: DataType> {
+    /// skipped
+    Clip,
+    /// taken from the opposite side of the grid
+    Wrap,
+    /// filled with the constant, e.g., `Boundary::Constant(&0)`
+    Constant(&'a T),
+}
+
+pub struct Neighbors<'a, T: DataType> {
+    grid: &'a Grid<T>,
+    row: isize,
+    col: isize,
+    offsets: &'a [(isize, isize)],
+    boundary: Boundary<'a, T>,
+    index: usize,
+}
+
+impl<'a, T: DataType> Iterator for Neighbors<'a, T> {
+    // the coordinates are signed, since constant filled neighbors lie outside of the grid
+    type Item = (isize, isize, &'a T);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (rows, cols) = (self.grid.rows as isize, self.grid.cols as isize);
+        while self.index < self.offsets.len() {
+            let (dr, dc) = self.offsets[self.index];
+            self.index += 1;
+            let (r, c) = (self.row + dr, self.col + dc);
+            if let Some(value) = self.grid.get(r, c) {
+                return Some((r, c, value));
+            }
+            match self.boundary {
+                Boundary::Clip => (),
+                Boundary::Wrap => {
+                    let (r, c) = (r.rem_euclid(rows), c.rem_euclid(cols));
+                    return Some((r, c, self.grid.at(r as usize, c as usize)));
+                }
+                Boundary::Constant(value) => return Some((r, c, value)),
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T: DataType> Grid<T> {
+    /// Neighbors of the cell at (row, col) in the order of the offsets, cells outside of
+    /// the grid are skipped.
+    pub fn neighbors(
+        &'a self,
+        row: usize,
+        col: usize,
+        hood: Neighborhood<'a>,
+    ) -> Neighbors<'a, T> {
+        self.neighbors_with(row, col, hood, Boundary::Clip)
+    }
+
+    pub fn neighbors_with(
+        &'a self,
+        row: usize,
+        col: usize,
+        hood: Neighborhood<'a>,
+        boundary: Boundary<'a, T>,
+    ) -> Neighbors<'a, T> {
+        Neighbors {
+            grid: self,
+            row: row as isize,
+            col: col as isize,
+            offsets: hood.offsets(),
+            boundary: boundary,
+            index: 0,
+        }
+    }
+}
+
+#[test]
+fn test_neighbors() {
+    let grid = Grid {
+        rows: 3,
+        cols: 4,
+        data: vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+    };
+    let values = |it: Neighbors<i32>| it.map(|(_, _, v)| *v).collect::<Vec<i32>>();
+
+    assert_eq!(values(grid.neighbors(1, 1, Neighborhood::Moore)), vec![0, 1, 2, 4, 6, 8, 9, 10]);
+    assert_eq!(values(grid.neighbors(0, 0, Neighborhood::Moore)), vec![1, 4, 5]);
+    assert_eq!(values(grid.neighbors(0, 0, Neighborhood::VonNeumann)), vec![1, 4]);
+    assert_eq!(
+        grid.neighbors(2, 3, Neighborhood::VonNeumann)
+            .map(|(r, c, _)| (r, c))
+            .collect::<Vec<(isize, isize)>>(),
+        vec![(1, 3), (2, 2)]
+    );
+
+    let wrapped = grid.neighbors_with(0, 0, Neighborhood::VonNeumann, Boundary::Wrap);
+    assert_eq!(values(wrapped), vec![8, 3, 1, 4]);
+    let constant = grid.neighbors_with(0, 0, Neighborhood::VonNeumann, Boundary::Constant(&-1));
+    assert_eq!(
+        constant.collect::<Vec<(isize, isize, &i32)>>(),
+        vec![(-1, 0, &-1), (0, -1, &-1), (0, 1, &1), (1, 0, &4)]
+    );
+
+    let knight = [(-2, -1), (-2, 1), (-1, -2), (-1, 2), (1, -2), (1, 2), (2, -1), (2, 1)];
+    assert_eq!(values(grid.neighbors(0, 1, Neighborhood::Custom(&knight))), vec![7, 8, 10]);
+}
@@ -0,0 +1,210 @@
+use super::{DataType, Grid, GridView, IdxTransform};
+
+/// The 8 symmetries of a rectangle, i.e., the dihedral group D4, as runtime value. The
+/// rotations are counter clockwise like `Rot90` and friends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Identity,
+    Rot90,
+    Rot180,
+    Rot270,
+    FlipLr,
+    FlipUd,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Orientation {
+    pub const ALL: [Orientation; 8] = [
+        Orientation::Identity,
+        Orientation::Rot90,
+        Orientation::Rot180,
+        Orientation::Rot270,
+        Orientation::FlipLr,
+        Orientation::FlipUd,
+        Orientation::Transpose,
+        Orientation::AntiTranspose,
+    ];
+
+    // A view index (r, c) maps to the source index by first swapping r and c if `swap`
+    // and then mirroring the source row and/or column.
+    fn to_bits(self) -> (bool, bool, bool) {
+        match self {
+            Orientation::Identity => (false, false, false),
+            Orientation::Rot90 => (true, false, true),
+            Orientation::Rot180 => (false, true, true),
+            Orientation::Rot270 => (true, true, false),
+            Orientation::FlipLr => (false, false, true),
+            Orientation::FlipUd => (false, true, false),
+            Orientation::Transpose => (true, false, false),
+            Orientation::AntiTranspose => (true, true, true),
+        }
+    }
+
+    fn from_bits(bits: (bool, bool, bool)) -> Orientation {
+        *Orientation::ALL
+            .iter()
+            .find(|o| o.to_bits() == bits)
+            .unwrap()
+    }
+
+    pub fn swaps_axes(self) -> bool {
+        self.to_bits().0
+    }
+
+    /// First applies `self` and then `next`, like `Twice<Self, Next>`.
+    pub fn then(self, next: Orientation) -> Orientation {
+        let (swap_a, flip_row_a, flip_col_a) = self.to_bits();
+        let (swap_b, flip_row_b, flip_col_b) = next.to_bits();
+        let (flip_row_b, flip_col_b) = if swap_a {
+            (flip_col_b, flip_row_b)
+        } else {
+            (flip_row_b, flip_col_b)
+        };
+        Orientation::from_bits((
+            swap_a != swap_b,
+            flip_row_a != flip_row_b,
+            flip_col_a != flip_col_b,
+        ))
+    }
+
+    pub fn inverse(self) -> Orientation {
+        let (swap, flip_row, flip_col) = self.to_bits();
+        if swap {
+            Orientation::from_bits((swap, flip_col, flip_row))
+        } else {
+            self
+        }
+    }
+
+    /// Shape of a grid with the given shape after applying the orientation.
+    pub fn shape(self, rows: usize, cols: usize) -> (usize, usize) {
+        if self.swaps_axes() {
+            (cols, rows)
+        } else {
+            (rows, cols)
+        }
+    }
+
+    /// Maps an index of the oriented grid to the index of the source grid.
+    pub fn source_index(
+        self,
+        row: usize,
+        col: usize,
+        src_rows: usize,
+        src_cols: usize,
+    ) -> (usize, usize) {
+        let (swap, flip_row, flip_col) = self.to_bits();
+        let (r, c) = if swap { (col, row) } else { (row, col) };
+        (
+            if flip_row { src_rows - 1 - r } else { r },
+            if flip_col { src_cols - 1 - c } else { c },
+        )
+    }
+}
+
+/// Index transformation chosen at runtime. Since `make` has no orientation, it creates
+/// the identity. Use `Grid::oriented_view` instead.
+#[derive(Clone, Copy, Debug)]
+pub struct OrientationTf {
+    orientation: Orientation,
+    src_rows: usize,
+    src_cols: usize,
+}
+
+impl OrientationTf {
+    pub fn new(orientation: Orientation, src_rows: usize, src_cols: usize) -> OrientationTf {
+        OrientationTf {
+            orientation: orientation,
+            src_rows: src_rows,
+            src_cols: src_cols,
+        }
+    }
+}
+
+impl IdxTransform for OrientationTf {
+    fn apply(&self, row: usize, col: usize) -> (usize, usize) {
+        self.orientation
+            .source_index(row, col, self.src_rows, self.src_cols)
+    }
+    fn tf_rows(&self) -> usize {
+        self.orientation.shape(self.src_rows, self.src_cols).0
+    }
+    fn tf_cols(&self) -> usize {
+        self.orientation.shape(self.src_rows, self.src_cols).1
+    }
+    fn make(rows: usize, cols: usize) -> Self {
+        OrientationTf::new(Orientation::Identity, rows, cols)
+    }
+}
+
+impl<'a, T: DataType> Grid<T> {
+    pub fn oriented_view(&'a self, orientation: Orientation) -> GridView<'a, T, OrientationTf> {
+        GridView {
+            row_start: 0,
+            row_end: self.rows,
+            col_start: 0,
+            col_end: self.cols,
+            grid: self,
+            tf: OrientationTf::new(orientation, self.rows, self.cols),
+        }
+    }
+
+    pub fn orient(&self, orientation: Orientation) -> Grid<T> {
+        self.oriented_view(orientation).to_grid()
+    }
+}
+
+#[test]
+fn test_orientation() {
+    use super::{FlipLr, FlipUd, Rot180, Rot270, Rot90, Twice};
+
+    let grid = Grid {
+        rows: 2,
+        cols: 3,
+        data: vec![1, 2, 3, 4, 5, 6],
+    };
+    assert_eq!(grid.orient(Orientation::Identity), grid);
+    assert_eq!(grid.orient(Orientation::Rot90), grid.rot90());
+    assert_eq!(grid.orient(Orientation::Rot180), grid.transform::<Rot180>());
+    assert_eq!(grid.orient(Orientation::Rot270), grid.transform::<Rot270>());
+    assert_eq!(grid.orient(Orientation::FlipLr), grid.fliplr());
+    assert_eq!(grid.orient(Orientation::FlipUd), grid.flipud());
+    assert_eq!(
+        grid.orient(Orientation::Transpose).data,
+        vec![1, 4, 2, 5, 3, 6]
+    );
+    assert_eq!(
+        grid.orient(Orientation::AntiTranspose).data,
+        vec![6, 3, 5, 2, 4, 1]
+    );
+    assert_eq!(
+        grid.orient(Orientation::Rot90.then(Orientation::FlipLr)),
+        grid.transform::<Twice<Rot90, FlipLr>>()
+    );
+    assert_eq!(
+        grid.orient(Orientation::Rot270.then(Orientation::FlipUd)),
+        grid.transform::<Twice<Rot270, FlipUd>>()
+    );
+    assert_eq!(
+        Orientation::Rot90.then(Orientation::FlipLr.then(Orientation::Rot270)),
+        Orientation::FlipUd
+    );
+
+    for (a, b) in iproduct!(Orientation::ALL.iter(), Orientation::ALL.iter()) {
+        assert_eq!(grid.orient(a.then(*b)), grid.orient(*a).orient(*b));
+    }
+    for o in Orientation::ALL.iter() {
+        assert_eq!(o.then(o.inverse()), Orientation::Identity);
+        assert_eq!(grid.orient(*o).orient(o.inverse()), grid);
+    }
+    let distinct = Orientation::ALL
+        .iter()
+        .map(|o| grid.orient(*o).data)
+        .collect::<std::collections::HashSet<Vec<i32>>>();
+    assert_eq!(distinct.len(), 8);
+    assert_eq!(Orientation::Rot90.shape(2, 3), (3, 2));
+    let view = grid.oriented_view(Orientation::Rot90);
+    assert_eq!((view.rows(), view.cols()), (3, 2));
+    assert_eq!(*view.at(0, 0), 3);
+}
@@ -0,0 +1,269 @@
+use super::{DataType, Grid, Neighborhood, Point};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// Distance of unreachable cells in distance fields.
+pub const UNREACHABLE: u32 = u32::MAX;
+const NO_PREDECESSOR: usize = usize::MAX;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathResult {
+    /// cells from start to goal, both included
+    pub path: Vec<(usize, usize)>,
+    pub cost: u32,
+}
+
+/// Admissible A* heuristic for 4-way moves with cost at least 1.
+pub fn manhattan_distance(a: (usize, usize), b: (usize, usize)) -> u32 {
+    (Point::from(a) - Point::from(b)).manhattan() as u32
+}
+
+/// Admissible A* heuristic for 8-way moves with cost at least 1.
+pub fn chebyshev_distance(a: (usize, usize), b: (usize, usize)) -> u32 {
+    let d = Point::from(a) - Point::from(b);
+    d.row.abs().max(d.col.abs()) as u32
+}
+
+struct Search {
+    cols: usize,
+    dist: Vec<u32>,
+    prev: Vec<usize>,
+}
+
+impl Search {
+    fn new<T: DataType>(grid: &Grid<T>, start: (usize, usize)) -> Search {
+        let mut dist = vec![UNREACHABLE; grid.data.len()];
+        dist[start.0 * grid.cols + start.1] = 0;
+        Search {
+            cols: grid.cols,
+            dist: dist,
+            prev: vec![NO_PREDECESSOR; grid.data.len()],
+        }
+    }
+
+    fn path_to(&self, goal: (usize, usize)) -> Option<PathResult> {
+        let goal_idx = goal.0 * self.cols + goal.1;
+        let cost = self.dist[goal_idx];
+        if cost == UNREACHABLE {
+            return None;
+        }
+        let mut path = vec![goal];
+        let mut idx = goal_idx;
+        while self.prev[idx] != NO_PREDECESSOR {
+            idx = self.prev[idx];
+            path.push((idx / self.cols, idx % self.cols));
+        }
+        path.reverse();
+        Some(PathResult {
+            path: path,
+            cost: cost,
+        })
+    }
+
+    fn to_grid(self, rows: usize) -> Grid<u32> {
+        Grid {
+            rows: rows,
+            cols: self.cols,
+            data: self.dist,
+        }
+    }
+}
+
+impl<T: DataType> Grid<T> {
+    fn bfs_search<P: Fn(&T) -> bool>(
+        &self,
+        start: (usize, usize),
+        goal: Option<(usize, usize)>,
+        hood: Neighborhood,
+        passable: P,
+    ) -> Search {
+        let mut search = Search::new(self, start);
+        let mut queue = VecDeque::from(vec![start]);
+        while let Some((row, col)) = queue.pop_front() {
+            if Some((row, col)) == goal {
+                break;
+            }
+            let idx = row * self.cols + col;
+            for (r, c, value) in self.neighbors(row, col, hood) {
+                let n_idx = r as usize * self.cols + c as usize;
+                if search.dist[n_idx] == UNREACHABLE && passable(value) {
+                    search.dist[n_idx] = search.dist[idx] + 1;
+                    search.prev[n_idx] = idx;
+                    queue.push_back((r as usize, c as usize));
+                }
+            }
+        }
+        search
+    }
+
+    fn astar_search<P, C, H>(
+        &self,
+        start: (usize, usize),
+        goal: Option<(usize, usize)>,
+        hood: Neighborhood,
+        passable: P,
+        cost: C,
+        heuristic: H,
+    ) -> Search
+    where
+        P: Fn(&T) -> bool,
+        C: Fn(&T) -> u32,
+        H: Fn((usize, usize)) -> u32,
+    {
+        let mut search = Search::new(self, start);
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((heuristic(start), 0, start)));
+        while let Some(Reverse((_, dist, (row, col)))) = heap.pop() {
+            let idx = row * self.cols + col;
+            if dist > search.dist[idx] {
+                continue;
+            }
+            if Some((row, col)) == goal {
+                break;
+            }
+            for (r, c, value) in self.neighbors(row, col, hood) {
+                if !passable(value) {
+                    continue;
+                }
+                let n_idx = r as usize * self.cols + c as usize;
+                let n_dist = dist + cost(value);
+                if n_dist < search.dist[n_idx] {
+                    search.dist[n_idx] = n_dist;
+                    search.prev[n_idx] = idx;
+                    let cell = (r as usize, c as usize);
+                    heap.push(Reverse((n_dist + heuristic(cell), n_dist, cell)));
+                }
+            }
+        }
+        search
+    }
+
+    /// Fewest steps through passable cells, the start itself need not be passable.
+    pub fn bfs<P: Fn(&T) -> bool>(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        hood: Neighborhood,
+        passable: P,
+    ) -> Option<PathResult> {
+        self.bfs_search(start, Some(goal), hood, passable)
+            .path_to(goal)
+    }
+
+    /// Cheapest path, where entering a cell costs `cost` of its value.
+    pub fn dijkstra<P: Fn(&T) -> bool, C: Fn(&T) -> u32>(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        hood: Neighborhood,
+        passable: P,
+        cost: C,
+    ) -> Option<PathResult> {
+        self.astar_search(start, Some(goal), hood, passable, cost, |_| 0)
+            .path_to(goal)
+    }
+
+    /// Like `dijkstra` but guided by a heuristic that must not overestimate the remaining
+    /// cost, e.g., `manhattan_distance` to the goal.
+    pub fn astar<P, C, H>(
+        &self,
+        start: (usize, usize),
+        goal: (usize, usize),
+        hood: Neighborhood,
+        passable: P,
+        cost: C,
+        heuristic: H,
+    ) -> Option<PathResult>
+    where
+        P: Fn(&T) -> bool,
+        C: Fn(&T) -> u32,
+        H: Fn((usize, usize)) -> u32,
+    {
+        self.astar_search(start, Some(goal), hood, passable, cost, heuristic)
+            .path_to(goal)
+    }
+
+    /// Cheapest cost from start to every cell, `UNREACHABLE` where there is no path.
+    pub fn distance_field<P: Fn(&T) -> bool, C: Fn(&T) -> u32>(
+        &self,
+        start: (usize, usize),
+        hood: Neighborhood,
+        passable: P,
+        cost: C,
+    ) -> Grid<u32> {
+        self.astar_search(start, None, hood, passable, cost, |_| 0)
+            .to_grid(self.rows)
+    }
+
+    /// Number of steps from start to every cell, `UNREACHABLE` where there is no path.
+    pub fn step_field<P: Fn(&T) -> bool>(
+        &self,
+        start: (usize, usize),
+        hood: Neighborhood,
+        passable: P,
+    ) -> Grid<u32> {
+        self.bfs_search(start, None, hood, passable)
+            .to_grid(self.rows)
+    }
+}
+
+#[test]
+fn test_path() {
+    use super::CharMap;
+
+    let maze: Grid<u8> = Grid::from_lines_with(
+        &["S.#...", "..#.#.", "....#G", "##.##."],
+        &CharMap::new(&[('.', 1), ('#', 0), ('S', 1), ('G', 1)]),
+    )
+    .unwrap();
+    let (start, goal) = ((0, 0), (2, 5));
+    let open = |v: &u8| *v > 0;
+
+    let res = maze.bfs(start, goal, Neighborhood::VonNeumann, open).unwrap();
+    assert_eq!(res.cost, 11);
+    assert_eq!(res.path.len(), 12);
+    assert_eq!((res.path[0], res.path[11]), (start, goal));
+    for (a, b) in izip!(res.path.iter(), res.path.iter().skip(1)) {
+        assert_eq!(manhattan_distance(*a, *b), 1);
+        assert!(open(maze.at(b.0, b.1)));
+    }
+    let diagonal = maze.bfs(start, goal, Neighborhood::Moore, open).unwrap();
+    assert_eq!(diagonal.cost, 6);
+    assert_eq!(maze.bfs(start, (3, 5), Neighborhood::VonNeumann, |v| *v > 1), None);
+
+    let dijkstra = maze
+        .dijkstra(start, goal, Neighborhood::VonNeumann, open, |_| 1)
+        .unwrap();
+    assert_eq!(dijkstra.cost, 11);
+    let astar = maze
+        .astar(start, goal, Neighborhood::VonNeumann, open, |_| 1, |p| {
+            manhattan_distance(p, goal)
+        })
+        .unwrap();
+    assert_eq!(astar.cost, 11);
+    let astar = maze
+        .astar(start, goal, Neighborhood::Moore, open, |_| 1, |p| {
+            chebyshev_distance(p, goal)
+        })
+        .unwrap();
+    assert_eq!(astar.cost, 6);
+
+    // expensive cells are avoided if there is a cheaper detour
+    let weights = Grid {
+        rows: 3,
+        cols: 3,
+        data: vec![1u8, 9, 1, 1, 9, 1, 1, 1, 1],
+    };
+    let res = weights
+        .dijkstra((0, 0), (0, 2), Neighborhood::VonNeumann, |_| true, |v| *v as u32)
+        .unwrap();
+    assert_eq!(res.cost, 6);
+    assert_eq!(res.path.len(), 7);
+
+    let field = maze.step_field(start, Neighborhood::VonNeumann, open);
+    assert_eq!(field[0][0], 0);
+    assert_eq!(field[2][5], 11);
+    assert_eq!(field[0][2], UNREACHABLE);
+    let weighted = weights.distance_field((0, 0), Neighborhood::VonNeumann, |_| true, |v| *v as u32);
+    assert_eq!(weighted.data, vec![0, 9, 6, 1, 10, 5, 2, 3, 4]);
+}
@@ -0,0 +1,143 @@
+use super::{DataType, Grid, Orientation};
+
+#[derive(Clone, Debug)]
+pub struct PatternOptions<T: DataType> {
+    /// orientations of the template that are searched, in this order
+    pub orientations: Vec<Orientation>,
+    /// template cells with this value match anything
+    pub mask_value: Option<T>,
+    /// if false, a match that shares an unmasked cell with an earlier match is dropped
+    pub allow_overlap: bool,
+}
+
+impl<T: DataType> Default for PatternOptions<T> {
+    fn default() -> Self {
+        PatternOptions {
+            orientations: vec![Orientation::Identity],
+            mask_value: None,
+            allow_overlap: true,
+        }
+    }
+}
+
+/// Upper left corner of the oriented template in the grid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PatternMatch {
+    pub row: usize,
+    pub col: usize,
+    pub orientation: Orientation,
+}
+
+fn unmasked_cells<T: DataType>(
+    template: &Grid<T>,
+    orientation: Orientation,
+    mask_value: Option<T>,
+) -> Vec<(usize, usize, T)> {
+    let view = template.oriented_view(orientation);
+    iproduct!(0..view.rows(), 0..view.cols())
+        .map(|(r, c)| (r, c, *view.at(r, c)))
+        .filter(|(_, _, v)| Some(*v) != mask_value)
+        .collect()
+}
+
+impl<T: DataType> Grid<T> {
+    /// All positions where the unmasked cells of the template equal the grid, ordered by
+    /// orientation and then row-major.
+    pub fn find_pattern(&self, template: &Grid<T>, options: &PatternOptions<T>) -> Vec<PatternMatch> {
+        let mut covered = vec![false; self.data.len()];
+        let mut res: Vec<PatternMatch> = Vec::with_capacity(0);
+        for orientation in &options.orientations {
+            let (t_rows, t_cols) = orientation.shape(template.rows, template.cols);
+            if t_rows > self.rows || t_cols > self.cols {
+                continue;
+            }
+            let cells = unmasked_cells(template, *orientation, options.mask_value);
+            for (row, col) in iproduct!(0..self.rows + 1 - t_rows, 0..self.cols + 1 - t_cols) {
+                let is_match = cells
+                    .iter()
+                    .all(|(r, c, v)| *self.at(row + r, col + c) == *v);
+                if !is_match {
+                    continue;
+                }
+                let grid_indices = cells.iter().map(|(r, c, _)| (row + r) * self.cols + col + c);
+                if !options.allow_overlap {
+                    if grid_indices.clone().any(|i| covered[i]) {
+                        continue;
+                    }
+                    for i in grid_indices {
+                        covered[i] = true;
+                    }
+                }
+                res.push(PatternMatch {
+                    row: row,
+                    col: col,
+                    orientation: *orientation,
+                });
+            }
+        }
+        res
+    }
+
+    /// Sets the unmasked template cells of all matches to the value.
+    pub fn mark_matches(
+        &mut self,
+        template: &Grid<T>,
+        matches: &[PatternMatch],
+        mask_value: Option<T>,
+        value: T,
+    ) {
+        for m in matches {
+            for (r, c, _) in unmasked_cells(template, m.orientation, mask_value) {
+                self[m.row + r][m.col + c] = value;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_pattern() {
+    use super::BinaryCodec;
+
+    let grid: Grid<u8> = Grid::from_lines_with(
+        &["#.#..", ".##..", "#.#.#", "...#.", "..#.#"],
+        &BinaryCodec,
+    )
+    .unwrap();
+    let cross: Grid<u8> = Grid::from_lines_with(&["#.#", ".#.", "#.#"], &BinaryCodec).unwrap();
+    let options = PatternOptions {
+        mask_value: Some(0),
+        ..PatternOptions::default()
+    };
+    let matches = grid.find_pattern(&cross, &options);
+    let positions = matches.iter().map(|m| (m.row, m.col)).collect::<Vec<(usize, usize)>>();
+    assert_eq!(positions, vec![(0, 0), (2, 2)]);
+
+    // without mask, the zeros have to match as well
+    let strict = grid.find_pattern(&cross, &PatternOptions::default());
+    assert_eq!(strict.len(), 1);
+
+    let options = PatternOptions {
+        allow_overlap: false,
+        ..options
+    };
+    let overlapping: Grid<u8> =
+        Grid::from_lines_with(&["#.#.#", ".#.#.", "#.#.#"], &BinaryCodec).unwrap();
+    assert_eq!(overlapping.find_pattern(&cross, &options).len(), 1);
+
+    let corner: Grid<u8> = Grid::from_lines_with(&["##", "#."], &BinaryCodec).unwrap();
+    let target: Grid<u8> = Grid::from_lines_with(&["...", ".##", "..#"], &BinaryCodec).unwrap();
+    let options = PatternOptions {
+        orientations: Orientation::ALL.to_vec(),
+        mask_value: Some(0),
+        allow_overlap: true,
+    };
+    let matches = target.find_pattern(&corner, &options);
+    assert_eq!(matches.len(), 2);
+    assert_eq!((matches[0].row, matches[0].col), (1, 1));
+    assert_eq!(matches[0].orientation, Orientation::Rot270);
+    assert_eq!(matches[1].orientation, Orientation::FlipLr);
+
+    let mut marked = target.clone();
+    marked.mark_matches(&corner, &matches[..1], Some(0), 0);
+    assert_eq!(marked.data.iter().filter(|v| **v > 0).count(), 0);
+}
@@ -0,0 +1,155 @@
+use super::{DataType, Grid, GridView, IdxTransform};
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+
+/// Signed (row, col) position or offset, may lie outside of a grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Point {
+    pub fn new(row: isize, col: isize) -> Point {
+        Point { row: row, col: col }
+    }
+
+    /// Length of the shortest 4-way path to the origin.
+    pub fn manhattan(&self) -> isize {
+        self.row.abs() + self.col.abs()
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((row, col): (isize, isize)) -> Point {
+        Point::new(row, col)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((row, col): (usize, usize)) -> Point {
+        Point::new(row as isize, col as isize)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+    fn add(self, other: Point) -> Point {
+        Point::new(self.row + other.row, self.col + other.col)
+    }
+}
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+impl Sub for Point {
+    type Output = Point;
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.row - other.row, self.col - other.col)
+    }
+}
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+impl Neg for Point {
+    type Output = Point;
+    fn neg(self) -> Point {
+        Point::new(-self.row, -self.col)
+    }
+}
+impl Mul<isize> for Point {
+    type Output = Point;
+    fn mul(self, factor: isize) -> Point {
+        Point::new(self.row * factor, self.col * factor)
+    }
+}
+
+impl<T: DataType> Grid<T> {
+    pub fn contains(&self, point: Point) -> bool {
+        point.row >= 0
+            && point.col >= 0
+            && point.row < self.rows as isize
+            && point.col < self.cols as isize
+    }
+
+    /// None outside of the grid.
+    pub fn get(&self, row: isize, col: isize) -> Option<&T> {
+        if self.contains(Point::new(row, col)) {
+            Some(&self.data[row as usize * self.cols + col as usize])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, row: isize, col: isize) -> Option<&mut T> {
+        if self.contains(Point::new(row, col)) {
+            Some(&mut self.data[row as usize * self.cols + col as usize])
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T: DataType, TF: IdxTransform> GridView<'a, T, TF> {
+    pub fn contains(&self, point: Point) -> bool {
+        point.row >= 0
+            && point.col >= 0
+            && point.row < self.rows() as isize
+            && point.col < self.cols() as isize
+    }
+
+    /// None outside of the view.
+    pub fn get(&self, row: isize, col: isize) -> Option<&'a T> {
+        if self.contains(Point::new(row, col)) {
+            Some(self.at(row as usize, col as usize))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: DataType> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self[row][col]
+    }
+}
+impl<T: DataType> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self[row][col]
+    }
+}
+
+#[test]
+fn test_point() {
+    let mut grid = Grid {
+        rows: 2,
+        cols: 3,
+        data: vec![0, 1, 2, 3, 4, 5],
+    };
+    assert_eq!(grid.get(1, 2), Some(&5));
+    assert_eq!(grid.get(-1, 0), None);
+    assert_eq!(grid.get(0, 3), None);
+    assert_eq!(grid.get(2, 0), None);
+    *grid.get_mut(0, 1).unwrap() = 7;
+    assert!(grid.get_mut(0, -1).is_none());
+    grid[(1, 0)] += 10;
+    assert_eq!((grid[(0, 1)], grid[(1, 0)]), (7, 13));
+
+    let p = Point::from((1usize, 2usize));
+    assert!(grid.contains(p));
+    assert!(!grid.contains(p + Point::new(0, 1)));
+    assert!(!grid.contains(-p));
+    let mut q = p * 3 - Point::new(1, 1);
+    assert_eq!(q, Point::new(2, 5));
+    q += Point::from((-2isize, -5isize));
+    assert_eq!(q, Point::default());
+    q -= p;
+    assert_eq!(q.manhattan(), 3);
+
+    let view = grid.view(1..2, 1..3);
+    assert_eq!(view.get(0, 1), Some(&5));
+    assert_eq!(view.get(1, 0), None);
+}
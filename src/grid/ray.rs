@@ -0,0 +1,136 @@
+use super::{DataType, Grid, GridView, IdxTransform, Point};
+
+/// First cell on a ray for which the stop predicate holds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit<'a, T: DataType> {
+    pub row: usize,
+    pub col: usize,
+    pub value: &'a T,
+    /// number of steps from the origin
+    pub distance: usize,
+}
+
+/// Shared by grids and views, get returns None outside.
+fn cast_ray_with<'a, T: DataType, G: Fn(Point) -> Option<&'a T>, P: Fn(&T) -> bool>(
+    get: G,
+    origin: (usize, usize),
+    direction: (isize, isize),
+    stop_predicate: P,
+) -> Option<RayHit<'a, T>> {
+    let direction = Point::from(direction);
+    if direction == Point::default() {
+        return None;
+    }
+    let mut position = Point::from(origin);
+    let mut distance = 0;
+    loop {
+        position += direction;
+        distance += 1;
+        let value = get(position)?;
+        if stop_predicate(value) {
+            return Some(RayHit {
+                row: position.row as usize,
+                col: position.col as usize,
+                value: value,
+                distance: distance,
+            });
+        }
+    }
+}
+
+impl<'a, T: DataType, TF: IdxTransform> GridView<'a, T, TF> {
+    /// Walks from the origin, excluding the origin itself, into the direction (row step,
+    /// col step) until the predicate holds or the view is left.
+    pub fn cast_ray<P: Fn(&T) -> bool>(
+        &self,
+        origin: (usize, usize),
+        direction: (isize, isize),
+        stop_predicate: P,
+    ) -> Option<RayHit<'a, T>> {
+        cast_ray_with(|p| self.get(p.row, p.col), origin, direction, stop_predicate)
+    }
+}
+
+impl<'a, T: DataType, TF: IdxTransform + Copy + 'a> GridView<'a, T, TF> {
+    /// One ray per direction, directions without hit are skipped.
+    pub fn visible_from<'d, P: Fn(&T) -> bool + 'd>(
+        &self,
+        origin: (usize, usize),
+        directions: &'d [(isize, isize)],
+        predicate: P,
+    ) -> impl Iterator<Item = RayHit<'a, T>> + 'd
+    where
+        'a: 'd,
+    {
+        let view = *self;
+        directions
+            .iter()
+            .filter_map(move |d| view.cast_ray(origin, *d, &predicate))
+    }
+}
+
+// grids do not go through their views, since day 11 casts rays from every cell
+impl<'a, T: DataType> Grid<T> {
+    pub fn cast_ray<P: Fn(&T) -> bool>(
+        &'a self,
+        origin: (usize, usize),
+        direction: (isize, isize),
+        stop_predicate: P,
+    ) -> Option<RayHit<'a, T>> {
+        cast_ray_with(|p| self.get(p.row, p.col), origin, direction, stop_predicate)
+    }
+
+    pub fn visible_from<'d, P: Fn(&T) -> bool + 'd>(
+        &'a self,
+        origin: (usize, usize),
+        directions: &'d [(isize, isize)],
+        predicate: P,
+    ) -> impl Iterator<Item = RayHit<'a, T>> + 'd
+    where
+        'a: 'd,
+    {
+        directions
+            .iter()
+            .filter_map(move |d| self.cast_ray(origin, *d, &predicate))
+    }
+}
+
+#[test]
+fn test_ray() {
+    use super::{Rot90, MOORE_OFFSETS};
+
+    let grid = Grid {
+        rows: 3,
+        cols: 4,
+        data: vec![0, 0, 0, 5, 0, 0, 0, 0, 7, 0, 0, 0],
+    };
+    let hit = grid.cast_ray((0, 0), (0, 1), |v| *v > 0).unwrap();
+    assert_eq!((hit.row, hit.col, *hit.value, hit.distance), (0, 3, 5, 3));
+    assert_eq!(grid.cast_ray((0, 0), (1, 1), |v| *v > 0), None);
+    assert_eq!(grid.cast_ray((0, 0), (0, 0), |v| *v > 0), None);
+    // the origin itself is never hit
+    assert_eq!(grid.cast_ray((0, 3), (0, 1), |v| *v > 0), None);
+    let hit = grid.cast_ray((2, 3), (0, -1), |v| *v > 0).unwrap();
+    assert_eq!((*hit.value, hit.distance), (7, 3));
+
+    let visible = grid.visible_from((1, 1), &MOORE_OFFSETS, |v| *v > 0);
+    assert_eq!(visible.map(|h| *h.value).collect::<Vec<i32>>(), vec![7]);
+    let visible = grid.visible_from((2, 1), &MOORE_OFFSETS, |v| *v > 0);
+    assert_eq!(
+        visible.map(|h| (h.row, h.col)).collect::<Vec<(usize, usize)>>(),
+        vec![(0, 3), (2, 0)]
+    );
+
+    // in the rotated 4x3 view, the 5 is at (0, 0) and the 7 at (3, 2)
+    let rotated = grid.as_tf_view::<Rot90>();
+    let hit = rotated.cast_ray((0, 2), (1, -1), |v| *v > 0);
+    assert_eq!(hit, None);
+    let hit = rotated.cast_ray((3, 0), (0, 1), |v| *v > 0).unwrap();
+    assert_eq!((hit.row, hit.col, *hit.value, hit.distance), (3, 2, 7, 2));
+    let hit = rotated.cast_ray((3, 0), (-1, 0), |v| *v > 0).unwrap();
+    assert_eq!((hit.row, hit.col, *hit.value), (0, 0, 5));
+    // the hits can be collected into a buffer that is reused
+    let mut values = vec![1];
+    values.extend(rotated.visible_from((1, 0), &MOORE_OFFSETS, |v| *v > 0).map(|h| *h.value));
+    assert_eq!(values, vec![1, 5, 7]);
+}
@@ -0,0 +1,134 @@
+use super::{DataType, Grid, GridView, IdxTransform};
+use std::ops::Range;
+
+impl<T: DataType> Grid<T> {
+    fn filled(rows: usize, cols: usize, value: T) -> Grid<T> {
+        Grid {
+            rows: rows,
+            cols: cols,
+            data: vec![value; rows * cols],
+        }
+    }
+
+    /// Surrounds the grid with a border of n cells of value.
+    pub fn pad(&self, n: usize, value: T) -> Grid<T> {
+        let mut res = Grid::filled(self.rows + 2 * n, self.cols + 2 * n, value);
+        res.view_mut(n..n + self.rows, n..n + self.cols)
+            .copy_from(&self.as_view());
+        res
+    }
+
+    /// Copy of the cells in the ranges.
+    pub fn crop(&self, rows: Range<usize>, cols: Range<usize>) -> Grid<T> {
+        if rows.start > rows.end
+            || cols.start > cols.end
+            || rows.end > self.rows
+            || cols.end > self.cols
+        {
+            panic!(
+                "Cannot crop {:?}x{:?} from grid of shape {}x{}",
+                rows, cols, self.rows, self.cols
+            );
+        }
+        self.view(rows, cols).to_grid()
+    }
+
+    /// Views next to each other from left to right, all need the same number of rows.
+    pub fn hstack<TF: IdxTransform>(views: &[GridView<T, TF>]) -> Grid<T> {
+        let rows = views.first().map_or(0, |v| v.rows());
+        if let Some(v) = views.iter().find(|v| v.rows() != rows) {
+            panic!("Cannot stack views with {} and {} rows side by side", rows, v.rows());
+        }
+        let cols = views.iter().map(|v| v.cols()).sum();
+        let mut res = Grid::filled(rows, cols, T::zero());
+        let mut col = 0;
+        for view in views {
+            res.view_mut(0..rows, col..col + view.cols()).copy_from(view);
+            col += view.cols();
+        }
+        res
+    }
+
+    /// Views below each other from top to bottom, all need the same number of cols.
+    pub fn vstack<TF: IdxTransform>(views: &[GridView<T, TF>]) -> Grid<T> {
+        let cols = views.first().map_or(0, |v| v.cols());
+        if let Some(v) = views.iter().find(|v| v.cols() != cols) {
+            panic!("Cannot stack views with {} and {} cols on top", cols, v.cols());
+        }
+        let rows = views.iter().map(|v| v.rows()).sum();
+        let mut res = Grid::filled(rows, cols, T::zero());
+        let mut row = 0;
+        for view in views {
+            res.view_mut(row..row + view.rows(), 0..cols).copy_from(view);
+            row += view.rows();
+        }
+        res
+    }
+
+    /// Mosaic of rows of tiles. The tiles of a row need the same number of rows and all
+    /// rows of tiles the same number of cols.
+    pub fn from_tiles<TF: IdxTransform>(tiles: &[Vec<GridView<T, TF>>]) -> Grid<T> {
+        let row_cols = |row: &Vec<GridView<T, TF>>| row.iter().map(|v| v.cols()).sum::<usize>();
+        let row_rows = |row: &Vec<GridView<T, TF>>| row.first().map_or(0, |v| v.rows());
+        let cols = tiles.first().map_or(0, row_cols);
+        let mut res = Grid::filled(tiles.iter().map(row_rows).sum(), cols, T::zero());
+        let mut row = 0;
+        for tile_row in tiles {
+            let rows = row_rows(tile_row);
+            if let Some(v) = tile_row.iter().find(|v| v.rows() != rows) {
+                panic!("Cannot put tiles with {} and {} rows side by side", rows, v.rows());
+            }
+            if row_cols(tile_row) != cols {
+                panic!(
+                    "Cannot put rows of tiles with {} and {} cols on top",
+                    cols,
+                    row_cols(tile_row)
+                );
+            }
+            let mut col = 0;
+            for tile in tile_row {
+                res.view_mut(row..row + rows, col..col + tile.cols()).copy_from(tile);
+                col += tile.cols();
+            }
+            row += rows;
+        }
+        res
+    }
+}
+
+#[test]
+fn test_reshape() {
+    use super::{BinaryCodec, Rot90};
+
+    let grid: Grid<u8> = Grid::from_lines_with(&["#.", "##"], &BinaryCodec).unwrap();
+    let padded = grid.pad(1, 0);
+    assert_eq!(
+        padded.to_lines_with(&BinaryCodec),
+        vec!["....", ".#..", ".##.", "...."]
+    );
+    assert_eq!(grid.pad(0, 1), grid);
+    assert_eq!(padded.crop(1..3, 1..3), grid);
+    assert_eq!(padded.crop(0..1, 0..4).data, vec![0; 4]);
+
+    let side = Grid::hstack(&[grid.as_view(), grid.view(0..2, 1..2)]);
+    assert_eq!(side.to_lines_with(&BinaryCodec), vec!["#..", "###"]);
+    let rotated = grid.as_tf_view::<Rot90>();
+    let stacked = Grid::vstack(&[rotated, rotated]);
+    assert_eq!(stacked.to_lines_with(&BinaryCodec), vec![".#", "##", ".#", "##"]);
+
+    let inner = padded.view(1..3, 1..3);
+    let mosaic = Grid::from_tiles(&[
+        vec![inner, padded.view(1..3, 0..1)],
+        vec![padded.view(0..1, 0..3)],
+    ]);
+    assert_eq!(
+        mosaic.to_lines_with(&BinaryCodec),
+        vec!["#..", "##.", "..."]
+    );
+    assert_eq!(Grid::<u8>::from_tiles::<super::Identity>(&[]).data.len(), 0);
+    let uneven = [vec![inner], vec![padded.view(0..1, 0..3)]];
+    assert!(std::panic::catch_unwind(|| Grid::from_tiles(&uneven)).is_err());
+    let backwards = Range { start: 2, end: 1 };
+    assert!(std::panic::catch_unwind(|| padded.crop(backwards.clone(), 0..4)).is_err());
+    assert!(std::panic::catch_unwind(|| padded.crop(0..4, backwards)).is_err());
+}
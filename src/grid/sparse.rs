@@ -0,0 +1,261 @@
+use super::{CellCodec, DataType, Grid, Neighborhood, SeatCodec};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Inclusive bounds of the non-background cells.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    pub row_min: isize,
+    pub row_max: isize,
+    pub col_min: isize,
+    pub col_max: isize,
+}
+
+impl Bounds {
+    fn extend(&self, row: isize, col: isize) -> Bounds {
+        Bounds {
+            row_min: self.row_min.min(row),
+            row_max: self.row_max.max(row),
+            col_min: self.col_min.min(col),
+            col_max: self.col_max.max(col),
+        }
+    }
+    fn union(&self, other: &Bounds) -> Bounds {
+        self.extend(other.row_min, other.col_min)
+            .extend(other.row_max, other.col_max)
+    }
+    pub fn rows(&self) -> usize {
+        (self.row_max - self.row_min + 1) as usize
+    }
+    pub fn cols(&self) -> usize {
+        (self.col_max - self.col_min + 1) as usize
+    }
+    pub fn contains(&self, row: isize, col: isize) -> bool {
+        row >= self.row_min && row <= self.row_max && col >= self.col_min && col <= self.col_max
+    }
+}
+
+/// Unbounded grid with signed coordinates. Only cells that differ from the background are
+/// stored.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T: DataType> {
+    cells: HashMap<(isize, isize), T>,
+    background: T,
+    /// number of cells per row and col, such that the bounds shrink cheaply on removal
+    row_counts: BTreeMap<isize, usize>,
+    col_counts: BTreeMap<isize, usize>,
+    /// area of the dense grid this was created from, kept such that `to_grid` does not crop
+    extent: Option<Bounds>,
+}
+
+fn increment(counts: &mut BTreeMap<isize, usize>, key: isize) {
+    *counts.entry(key).or_insert(0) += 1;
+}
+
+fn decrement(counts: &mut BTreeMap<isize, usize>, key: isize) {
+    if let Some(count) = counts.get_mut(&key) {
+        *count -= 1;
+        if *count == 0 {
+            counts.remove(&key);
+        }
+    }
+}
+
+impl<T: DataType> SparseGrid<T> {
+    pub fn new(background: T) -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+            background: background,
+            row_counts: BTreeMap::new(),
+            col_counts: BTreeMap::new(),
+            extent: None,
+        }
+    }
+
+    /// Cell (r, c) of the grid ends up at (r, c) of the sparse grid. The shape of the grid is
+    /// kept, such that `to_grid` returns it unchanged even with background borders. Removing
+    /// cells does not shrink `to_grid` below this shape.
+    pub fn from_grid(grid: &Grid<T>, background: T) -> SparseGrid<T> {
+        let mut res = SparseGrid::new(background);
+        for (r, c) in iproduct!(0..grid.rows, 0..grid.cols) {
+            res.set(r as isize, c as isize, *grid.at(r, c));
+        }
+        if grid.rows > 0 && grid.cols > 0 {
+            res.extent = Some(Bounds {
+                row_min: 0,
+                row_max: grid.rows as isize - 1,
+                col_min: 0,
+                col_max: grid.cols as isize - 1,
+            });
+        }
+        res
+    }
+
+    pub fn background(&self) -> T {
+        self.background
+    }
+
+    pub fn get(&self, row: isize, col: isize) -> &T {
+        self.cells.get(&(row, col)).unwrap_or(&self.background)
+    }
+
+    /// Setting the background value removes the cell.
+    pub fn set(&mut self, row: isize, col: isize, value: T) {
+        if value == self.background {
+            self.remove(row, col);
+            return;
+        }
+        if self.cells.insert((row, col), value).is_none() {
+            increment(&mut self.row_counts, row);
+            increment(&mut self.col_counts, col);
+        }
+    }
+
+    pub fn remove(&mut self, row: isize, col: isize) -> Option<T> {
+        let removed = self.cells.remove(&(row, col));
+        if removed.is_some() {
+            decrement(&mut self.row_counts, row);
+            decrement(&mut self.col_counts, col);
+        }
+        removed
+    }
+
+    /// Number of non-background cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// None if all cells are background.
+    pub fn bounds(&self) -> Option<Bounds> {
+        Some(Bounds {
+            row_min: *self.row_counts.keys().next()?,
+            row_max: *self.row_counts.keys().next_back()?,
+            col_min: *self.col_counts.keys().next()?,
+            col_max: *self.col_counts.keys().next_back()?,
+        })
+    }
+
+    /// Non-background cells in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = ((isize, isize), &T)> {
+        self.cells.iter().map(|(idx, v)| (*idx, v))
+    }
+
+    /// Neighbors in the order of the offsets, missing cells are background.
+    pub fn neighbors<'a>(
+        &'a self,
+        row: isize,
+        col: isize,
+        hood: Neighborhood<'a>,
+    ) -> impl Iterator<Item = (isize, isize, &'a T)> + 'a {
+        hood.offsets()
+            .iter()
+            .map(move |(dr, dc)| (row + dr, col + dc, self.get(row + dr, col + dc)))
+    }
+
+    /// Dense grid within the bounds, its (0, 0) is the upper left corner of the bounds. For
+    /// sparse grids from `from_grid` the bounds include the source grid.
+    pub fn to_grid(&self) -> Grid<T> {
+        let bounds = match (self.bounds(), self.extent) {
+            (Some(b), Some(e)) => Some(b.union(&e)),
+            (b, e) => b.or(e),
+        };
+        match bounds {
+            None => Grid {
+                rows: 0,
+                cols: 0,
+                data: vec![],
+            },
+            Some(b) => Grid {
+                rows: b.rows(),
+                cols: b.cols(),
+                data: iproduct!(b.row_min..b.row_max + 1, b.col_min..b.col_max + 1)
+                    .map(|(r, c)| *self.get(r, c))
+                    .collect(),
+            },
+        }
+    }
+
+    pub fn to_lines_with<C: CellCodec<T>>(&self, codec: &C) -> Vec<String> {
+        self.to_grid().to_lines_with(codec)
+    }
+}
+
+impl<T: DataType> fmt::Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_grid().display_with(&SeatCodec))
+    }
+}
+
+#[test]
+fn test_sparse() {
+    use super::BinaryCodec;
+
+    let mut grid = SparseGrid::new(0u8);
+    assert_eq!(grid.bounds(), None);
+    assert_eq!(grid.to_grid().data.len(), 0);
+    grid.set(-2, 3, 1);
+    grid.set(1, -1, 1);
+    grid.set(0, 0, 1);
+    assert_eq!(*grid.get(-2, 3), 1);
+    assert_eq!(*grid.get(100, -100), 0);
+    assert_eq!(
+        grid.bounds(),
+        Some(Bounds {
+            row_min: -2,
+            row_max: 1,
+            col_min: -1,
+            col_max: 3
+        })
+    );
+    assert_eq!(
+        grid.to_lines_with(&BinaryCodec),
+        vec!["....#", ".....", ".#...", "#...."]
+    );
+
+    grid.set(-2, 3, 0);
+    assert_eq!(grid.len(), 2);
+    let b = grid.bounds().unwrap();
+    assert_eq!((b.row_min, b.col_max, b.rows(), b.cols()), (0, 0, 2, 2));
+    assert_eq!(
+        grid.neighbors(1, 0, Neighborhood::VonNeumann)
+            .map(|(_, _, v)| *v)
+            .collect::<Vec<u8>>(),
+        vec![1, 1, 0, 0]
+    );
+
+    let dense = Grid {
+        rows: 2,
+        cols: 3,
+        data: vec![0u8, 2, 0, 1, 0, 2],
+    };
+    let sparse = SparseGrid::from_grid(&dense, 0);
+    assert_eq!(sparse.len(), 3);
+    assert_eq!(sparse.to_grid(), dense);
+    assert_eq!(format!("{}", sparse), ".#.\nL.#");
+    assert!(!sparse.is_empty());
+    assert!(SparseGrid::new(0u8).is_empty());
+
+    // borders of background are kept, also when cells are set outside of the source grid
+    let bordered: Grid<u8> =
+        Grid::from_lines_with(&["....", ".#..", "...."], &BinaryCodec).unwrap();
+    let mut sparse = SparseGrid::from_grid(&bordered, 0);
+    assert_eq!(sparse.bounds().unwrap().rows(), 1);
+    assert_eq!(sparse.to_grid(), bordered);
+    assert_eq!(SparseGrid::from_grid(&bordered.crop(0..3, 2..4), 0).to_grid().rows, 3);
+    sparse.set(-1, 1, 1);
+    assert_eq!(
+        sparse.to_lines_with(&BinaryCodec),
+        vec![".#..", "....", ".#..", "...."]
+    );
+    // removing cells shrinks the bounds but not below the source grid
+    sparse.set(1, 1, 0);
+    let b = sparse.bounds().unwrap();
+    assert_eq!((b.row_min, b.row_max, b.col_min, b.col_max), (-1, -1, 1, 1));
+    assert_eq!(sparse.remove(-1, 1), Some(1));
+    assert_eq!(sparse.remove(-1, 1), None);
+    assert_eq!(sparse.bounds(), None);
+    assert_eq!(sparse.to_lines_with(&BinaryCodec), vec!["....", "....", "...."]);
+}
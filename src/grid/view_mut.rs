@@ -0,0 +1,169 @@
+use super::{DataType, Grid, GridView, Identity, IdxTransform};
+use std::ops::{Index, IndexMut, Range};
+
+/// Like `GridView` but allows writing into the viewed region of the grid.
+pub struct GridViewMut<'a, T: DataType, TF: IdxTransform> {
+    pub row_start: usize,
+    pub col_start: usize,
+    pub row_end: usize,
+    pub col_end: usize,
+    pub grid: &'a mut Grid<T>,
+    pub tf: TF,
+}
+
+impl<'a, T: DataType> Grid<T> {
+    pub fn as_view_mut(&'a mut self) -> GridViewMut<'a, T, Identity> {
+        let (rows, cols) = (self.rows, self.cols);
+        self.transformed_view_mut::<Identity>(0..rows, 0..cols)
+    }
+
+    pub fn view_mut(
+        &'a mut self,
+        row_range: Range<usize>,
+        col_range: Range<usize>,
+    ) -> GridViewMut<'a, T, Identity> {
+        self.transformed_view_mut::<Identity>(row_range, col_range)
+    }
+
+    /// First extracts the view, then applies the transformation.
+    pub fn transformed_view_mut<TF: IdxTransform>(
+        &'a mut self,
+        row_range: Range<usize>,
+        col_range: Range<usize>,
+    ) -> GridViewMut<'a, T, TF> {
+        if row_range.end > self.rows || col_range.end > self.cols {
+            panic!(
+                "View {:?}x{:?} exceeds grid of shape {}x{}",
+                row_range, col_range, self.rows, self.cols
+            );
+        }
+        GridViewMut {
+            row_start: row_range.start,
+            row_end: row_range.end,
+            col_start: col_range.start,
+            col_end: col_range.end,
+            grid: self,
+            tf: TF::make(
+                row_range.end - row_range.start,
+                col_range.end - col_range.start,
+            ),
+        }
+    }
+}
+
+impl<'a, T: DataType, TF: IdxTransform> GridViewMut<'a, T, TF> {
+    pub fn rows(&self) -> usize {
+        self.tf.tf_rows()
+    }
+    pub fn cols(&self) -> usize {
+        self.tf.tf_cols()
+    }
+
+    fn data_index(&self, row: usize, col: usize) -> usize {
+        if row >= self.rows() || col >= self.cols() {
+            panic!(
+                "Index ({}, {}) out of bounds for view of shape {}x{}",
+                row,
+                col,
+                self.rows(),
+                self.cols()
+            );
+        }
+        let (t_row, t_col) = self.tf.apply(row, col);
+        (t_row + self.row_start) * self.grid.cols + t_col + self.col_start
+    }
+
+    pub fn at(&self, row: usize, col: usize) -> &T {
+        &self.grid.data[self.data_index(row, col)]
+    }
+    pub fn at_mut(&mut self, row: usize, col: usize) -> &mut T {
+        let idx = self.data_index(row, col);
+        &mut self.grid.data[idx]
+    }
+
+    pub fn fill(&mut self, value: T) {
+        for (r, c) in iproduct!(0..self.rows(), 0..self.cols()) {
+            *self.at_mut(r, c) = value;
+        }
+    }
+
+    /// The source must have the same shape as this view.
+    pub fn copy_from<TF2: IdxTransform>(&mut self, source: &GridView<T, TF2>) {
+        if (source.rows(), source.cols()) != (self.rows(), self.cols()) {
+            panic!(
+                "Cannot copy view of shape {}x{} into view of shape {}x{}",
+                source.rows(),
+                source.cols(),
+                self.rows(),
+                self.cols()
+            );
+        }
+        for (r, c) in iproduct!(0..self.rows(), 0..self.cols()) {
+            *self.at_mut(r, c) = *source.at(r, c);
+        }
+    }
+
+    /// Read-only view of the same region with the same transformation.
+    pub fn as_view(&self) -> GridView<'_, T, TF>
+    where
+        TF: Clone,
+    {
+        GridView {
+            row_start: self.row_start,
+            row_end: self.row_end,
+            col_start: self.col_start,
+            col_end: self.col_end,
+            grid: self.grid,
+            tf: self.tf.clone(),
+        }
+    }
+}
+
+impl<'a, T: DataType, TF: IdxTransform> Index<(usize, usize)> for GridViewMut<'a, T, TF> {
+    type Output = T;
+    fn index(&self, idx: (usize, usize)) -> &Self::Output {
+        self.at(idx.0, idx.1)
+    }
+}
+impl<'a, T: DataType, TF: IdxTransform> IndexMut<(usize, usize)> for GridViewMut<'a, T, TF> {
+    fn index_mut(&mut self, idx: (usize, usize)) -> &mut Self::Output {
+        self.at_mut(idx.0, idx.1)
+    }
+}
+
+#[test]
+fn test_view_mut() {
+    use super::Rot90;
+
+    let mut grid = Grid {
+        rows: 3,
+        cols: 4,
+        data: vec![0; 12],
+    };
+    grid.view_mut(1..3, 2..4).fill(7);
+    assert_eq!(grid.data, vec![0, 0, 0, 0, 0, 0, 7, 7, 0, 0, 7, 7]);
+
+    let mut view = grid.view_mut(0..2, 0..3);
+    view[(1, 2)] = 5;
+    assert_eq!(*view.at(1, 2), 5);
+    assert_eq!(view.as_view().to_grid().data, vec![0, 0, 0, 0, 0, 5]);
+    assert_eq!(grid[1][2], 5);
+
+    let source = Grid {
+        rows: 2,
+        cols: 3,
+        data: vec![1, 2, 3, 4, 5, 6],
+    };
+    let mut target = Grid {
+        rows: 4,
+        cols: 4,
+        data: vec![0; 16],
+    };
+    // the rotated 3x2 target region is filled with the 2x3 source
+    let mut rotated = target.transformed_view_mut::<Rot90>(1..3, 0..3);
+    assert_eq!((rotated.rows(), rotated.cols()), (3, 2));
+    rotated.copy_from(&source.as_tf_view::<Rot90>());
+    assert_eq!(target.view(1..3, 0..3).to_grid(), source);
+    target.as_view_mut()[(3, 3)] = 9;
+    assert_eq!(target[3][3], 9);
+}
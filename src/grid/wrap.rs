@@ -0,0 +1,126 @@
+use super::{DataType, Grid, GridView, Identity, IdxTransform};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WrapAxes {
+    Rows,
+    Cols,
+    Both,
+}
+
+/// Toroidal view, indices beyond the wrapped axes continue at the start of the view.
+#[derive(Clone, Copy)]
+pub struct WrappingView<'a, T: DataType, TF: IdxTransform> {
+    pub view: GridView<'a, T, TF>,
+    pub axes: WrapAxes,
+}
+
+impl<'a, T: DataType, TF: IdxTransform> WrappingView<'a, T, TF> {
+    /// Panics if the index exceeds an axis that does not wrap.
+    pub fn at(&self, row: usize, col: usize) -> &'a T {
+        let (rows, cols) = (self.view.rows(), self.view.cols());
+        let row = match self.axes {
+            WrapAxes::Rows | WrapAxes::Both => row % rows,
+            WrapAxes::Cols => row,
+        };
+        let col = match self.axes {
+            WrapAxes::Cols | WrapAxes::Both => col % cols,
+            WrapAxes::Rows => col,
+        };
+        if row >= rows || col >= cols {
+            panic!(
+                "Index ({}, {}) out of bounds for view of shape {}x{} wrapping {:?}",
+                row, col, rows, cols, self.axes
+            );
+        }
+        self.view.at(row, col)
+    }
+}
+
+/// The view repeated infinitely in all directions, e.g., (-1, -1) is the bottom right cell.
+#[derive(Clone, Copy)]
+pub struct TiledView<'a, T: DataType, TF: IdxTransform> {
+    pub view: GridView<'a, T, TF>,
+}
+
+impl<'a, T: DataType, TF: IdxTransform> TiledView<'a, T, TF> {
+    pub fn at(&self, row: isize, col: isize) -> &'a T {
+        let (rows, cols) = (self.view.rows() as isize, self.view.cols() as isize);
+        self.view
+            .at(row.rem_euclid(rows) as usize, col.rem_euclid(cols) as usize)
+    }
+}
+
+impl<'a, T: DataType, TF: IdxTransform> GridView<'a, T, TF> {
+    /// Panics if a wrapped axis is empty, since there is nothing to wrap around to.
+    pub fn wrapping(self, axes: WrapAxes) -> WrappingView<'a, T, TF> {
+        let empty_rows = self.rows() == 0 && axes != WrapAxes::Cols;
+        let empty_cols = self.cols() == 0 && axes != WrapAxes::Rows;
+        if empty_rows || empty_cols {
+            panic!(
+                "Cannot wrap {:?} of view with shape {}x{}",
+                axes,
+                self.rows(),
+                self.cols()
+            );
+        }
+        WrappingView {
+            view: self,
+            axes: axes,
+        }
+    }
+    /// Panics if the view is empty.
+    pub fn tiled(self) -> TiledView<'a, T, TF> {
+        if self.rows() == 0 || self.cols() == 0 {
+            panic!("Cannot tile view of shape {}x{}", self.rows(), self.cols());
+        }
+        TiledView { view: self }
+    }
+}
+
+impl<'a, T: DataType> Grid<T> {
+    pub fn wrapping_view(&'a self, axes: WrapAxes) -> WrappingView<'a, T, Identity> {
+        self.as_view().wrapping(axes)
+    }
+    pub fn tiled_view(&'a self) -> TiledView<'a, T, Identity> {
+        self.as_view().tiled()
+    }
+}
+
+#[test]
+fn test_wrap() {
+    use super::FlipLr;
+
+    let grid = Grid {
+        rows: 2,
+        cols: 3,
+        data: vec![1, 2, 3, 4, 5, 6],
+    };
+    let wrapped = grid.wrapping_view(WrapAxes::Cols);
+    assert_eq!(*wrapped.at(1, 3), 4);
+    assert_eq!(*wrapped.at(0, 3001), 2);
+    let wrapped = grid.wrapping_view(WrapAxes::Both);
+    assert_eq!(*wrapped.at(5, 5), 6);
+    let result = std::panic::catch_unwind(|| *grid.wrapping_view(WrapAxes::Rows).at(0, 3));
+    assert!(result.is_err());
+
+    let tiled = grid.tiled_view();
+    assert_eq!(*tiled.at(0, 0), 1);
+    assert_eq!(*tiled.at(-1, -1), 6);
+    assert_eq!(*tiled.at(-3, 4), 5);
+    assert_eq!(*tiled.at(2, -4), 3);
+
+    // wraps within the sub-view, not the whole grid
+    let tiled = grid.transformed_view::<FlipLr>(0..2, 1..3).tiled();
+    assert_eq!(
+        (0..5).map(|c| *tiled.at(1, c)).collect::<Vec<i32>>(),
+        vec![6, 5, 6, 5, 6]
+    );
+
+    // empty views are rejected when the wrapping view is created
+    let empty = grid.view(0..2, 1..1);
+    assert!(std::panic::catch_unwind(|| empty.tiled()).is_err());
+    assert!(std::panic::catch_unwind(|| empty.wrapping(WrapAxes::Cols)).is_err());
+    assert!(std::panic::catch_unwind(|| grid.view(0..0, 0..3).wrapping(WrapAxes::Both)).is_err());
+    let rows_only = empty.wrapping(WrapAxes::Rows);
+    assert!(std::panic::catch_unwind(|| *rows_only.at(3, 0)).is_err());
+}
@@ -0,0 +1,9 @@
+// The grid toolkit and the leveled trace output as a library, such that their whole API is
+// public and not only the parts the solvers of the binary use.
+#[macro_use]
+extern crate itertools;
+extern crate num;
+
+#[macro_use]
+pub mod trace;
+pub mod grid;
@@ -1,10 +1,21 @@
 #[macro_use]
 extern crate itertools;
 extern crate num;
+use std::env;
 use std::fs;
+use std::io::BufReader;
+use std::process;
 use std::time::Instant;
+#[macro_use]
+extern crate aoc2020;
+use aoc2020::{grid, trace};
 mod common;
-mod grid;
+mod compare;
+mod perf;
+mod serve;
+#[cfg(test)]
+mod snapshot;
+mod solvers;
 mod day01;
 mod day02;
 mod day03;
@@ -21,7 +32,9 @@ mod day13;
 mod day14;
 mod day19;
 mod day20;
-use common::TaskOfDay;
+use common::{stream_lines, TaskOfDay};
+
+type LineStream = Box<dyn Iterator<Item = String>>;
 
 fn read_file_with_blank_lines(path: &str) -> Vec<String> {
     let res: Vec<String> = fs::read_to_string(path)
@@ -34,42 +47,60 @@ fn read_file_with_blank_lines(path: &str) -> Vec<String> {
 }
 
 fn read_file(path: &String) -> Vec<String> {
-    let lines = read_file_with_blank_lines(&path);
-    let res: Vec<String> = lines
-        .iter()
+    let res: Vec<String> = read_file_with_blank_lines(&path)
+        .into_iter()
         .filter(|s| s.len() > 0)
-        .map(|s| s.clone())
         .collect();
     res
 }
 
-fn run_on_content<T>(f: fn(&Vec<String>, TaskOfDay) -> T, contents: &Vec<String>) -> (T, T) {
+fn open_lines(path: &str) -> LineStream {
+    let file = fs::File::open(path).expect("Could not read file.");
+    Box::new(stream_lines(BufReader::new(file)))
+}
+
+fn time_parts<T, F: Fn(TaskOfDay) -> T>(day: i32, f: F) -> (T, T) {
+    println!("Running day {}", day);
     let now = Instant::now();
-    let res_first = f(contents, TaskOfDay::First);
-    let elapsed = now.elapsed();
-    println!("Elapsed first:\t{:.2} millis", elapsed.as_millis());
+    let res_first = f(TaskOfDay::First);
+    let elapsed_first = now.elapsed();
+    println!("Elapsed first:\t{:.2} millis", elapsed_first.as_millis());
     let now = Instant::now();
-    let res_second = f(contents, TaskOfDay::Second);
-    let elapsed = now.elapsed();
-    println!("Elapsed second:\t{:.2} millis", elapsed.as_millis());
+    let res_second = f(TaskOfDay::Second);
+    let elapsed_second = now.elapsed();
+    println!("Elapsed second:\t{:.2} millis", elapsed_second.as_millis());
+    perf::record(day, elapsed_first, elapsed_second);
     (res_first, res_second)
 }
 
+fn run_on_content<T>(
+    day: i32,
+    f: fn(&Vec<String>, TaskOfDay) -> T,
+    contents: &Vec<String>,
+) -> (T, T) {
+    time_parts(day, |part| f(contents, part))
+}
+
 fn get_path(day: i32) -> String {
-    println!("Running day {}", day);
     format!("res/input_{:02}.txt", day)    
 }
 
 fn run<T>(day: i32, f: fn(&Vec<String>, TaskOfDay) -> T) -> (T, T) {
     let path = get_path(day);
     let contents: Vec<String> = read_file(&path);
-    run_on_content(f, &contents)
+    run_on_content(day, f, &contents)
 }
 
 fn run_with_blank_lines<T>(day: i32, f: fn(&Vec<String>, TaskOfDay) -> T) -> (T, T) {
     let path = get_path(day);
     let contents: Vec<String> = read_file_with_blank_lines(&path);
-    run_on_content(f, &contents)
+    run_on_content(day, f, &contents)
+}
+
+/// Each part gets its own pass over the file via `stream_lines`.
+fn run_streamed<T>(day: i32, f: fn(LineStream, TaskOfDay) -> T) -> (T, T) {
+    let path = get_path(day);
+    time_parts(day, |part| f(open_lines(&path), part))
 }
 
 fn print_res<T: std::fmt::Display>(res: (T, T)) {
@@ -80,8 +111,8 @@ fn unwrap_print_res<T: std::fmt::Display>(res: (Option<T>, Option<T>)) {
     println!("{}, {}\n", res.0.unwrap(), res.1.unwrap());
 }
 
-fn main() {
-    print_res(run(1, day01::run));
+fn run_all() {
+    print_res(run_streamed(1, day01::run_stream));
     print_res(run(2, day02::run));
     print_res(run(3, day03::run));
     print_res(run_with_blank_lines(4, day04::run));
@@ -89,16 +120,74 @@ fn main() {
     print_res(run_with_blank_lines(6, day06::run));
     print_res(run(7, day07::run));
     print_res(run(8, day08::run));
-    print_res(run(9, day09::run));
-    unwrap_print_res(run(10, day10::run));
+    print_res(run_streamed(9, day09::run_stream));
+    unwrap_print_res(run_streamed(10, day10::run_stream));
     unwrap_print_res(run(11, day11::run));
     unwrap_print_res(run(12, day12::run));
-    unwrap_print_res(run(13, day13::run));
+    unwrap_print_res(run_streamed(13, day13::run_stream));
     unwrap_print_res(run(14, day14::run));
     unwrap_print_res(run_with_blank_lines(19, day19::run));
     unwrap_print_res(run_with_blank_lines(20, day20::run));
-    
+    let history = perf::history_path();
+    match perf::append_history(&history, &perf::take_recorded()) {
+        Ok(_) => info!("appended timings to {}", history),
+        Err(e) => error!("could not append to timing history: {}", e),
+    }
+}
+
+
+fn bench_day11() {
+    let contents = read_file(&"res/input_11.txt".to_string());
+    let mut best = [u128::MAX; 2];
+    let mut all = [vec![], vec![]];
+    for _ in 0..25 {
+        for i in 0..2 {
+            let part = if i == 0 { TaskOfDay::First } else { TaskOfDay::Second };
+            let now = Instant::now();
+            let res = day11::run(&contents, part);
+            let t = now.elapsed().as_micros();
+            assert!(res.is_some());
+            best[i] = best[i].min(t);
+            all[i].push(t);
+        }
+    }
+    for a in all.iter_mut() { a.sort(); }
+    println!("BENCH min {} {} median {} {}", best[0], best[1], all[0][12], all[1][12]);
+}
 
+fn main() {
+    if std::env::var("BENCH11").is_ok() { bench_day11(); return; }
+    let args = trace::init(env::args().collect());
+    match args.get(1).map(|s| s.as_str()) {
+        None => run_all(),
+        Some("perf-diff") => match perf::perf_diff(&args[2..]) {
+            Ok(false) => (),
+            Ok(true) => process::exit(1),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(2);
+            }
+        },
+        // exiting also stops solver threads that timed out
+        Some("compare") => match compare::compare(&args[2..]) {
+            Ok(false) => process::exit(0),
+            Ok(true) => process::exit(1),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(2);
+            }
+        },
+        Some("serve") => {
+            if let Err(e) = serve::serve(&args[2..]) {
+                eprintln!("{}", e);
+                process::exit(2);
+            }
+        }
+        Some(cmd) => {
+            eprintln!("Unknown command '{}'", cmd);
+            process::exit(2);
+        }
+    }
 }
 
 #[test]
@@ -120,3 +209,11 @@ fn regression_tests() {
     assert_eq!(run_with_blank_lines(19, day19::run), (Some(213), Some(325)));
     assert_eq!(run_with_blank_lines(20, day20::run), (Some(111936085519519), Some(1792)));
 }
+
+#[test]
+fn streamed_regression_tests() {
+    assert_eq!(run_streamed(1, day01::run_stream), (1007331, 48914340));
+    assert_eq!(run_streamed(9, day09::run_stream), (1930745883, 268878261));
+    assert_eq!(run_streamed(10, day10::run_stream), (Some(2738), Some(74049191673856)));
+    assert_eq!(run_streamed(13, day13::run_stream), (Some(3464), Some(760171380521445)));
+}
@@ -0,0 +1,343 @@
+// Timing history of the runner and detection of performance regressions.
+//
+// Every run appends one line per day to the history file
+//     <unix timestamp in micros>,<git commit or ->,<day>,<micros first>,<micros second>
+// The timestamp identifies the run, such that two runs in the same second stay apart.
+// and `perf-diff` compares the latest run either with the latest run of a baseline commit
+// or with the median of the preceding runs.
+use lazy_static::lazy_static;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::{self, Command};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const HISTORY_FILE: &str = "timings_history.csv";
+const HISTORY_HEADER: &str = "timestamp,commit,day,first_micros,second_micros";
+const NO_COMMIT: &str = "-";
+// differences below this are considered noise, no matter what the relative change is
+const MIN_SLOWDOWN_MICROS: u128 = 1000;
+
+lazy_static! {
+    static ref RECORDED: Mutex<Vec<Timing>> = Mutex::new(Vec::with_capacity(0));
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timing {
+    pub day: i32,
+    pub first: u128,
+    pub second: u128,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Run {
+    timestamp: u64,
+    commit: String,
+    timings: Vec<Timing>,
+}
+
+impl Run {
+    fn get(&self, day: i32) -> Option<&Timing> {
+        self.timings.iter().find(|t| t.day == day)
+    }
+}
+
+/// Path of the history, tests write to a temporary file instead of the real history.
+pub fn history_path() -> String {
+    if cfg!(test) {
+        let name = format!("aoc_{}_{}", process::id(), HISTORY_FILE);
+        env::temp_dir().join(name).to_string_lossy().to_string()
+    } else {
+        HISTORY_FILE.to_string()
+    }
+}
+
+pub fn record(day: i32, first: Duration, second: Duration) {
+    RECORDED.lock().unwrap().push(Timing {
+        day: day,
+        first: first.as_micros(),
+        second: second.as_micros(),
+    });
+}
+
+pub fn take_recorded() -> Vec<Timing> {
+    RECORDED.lock().unwrap().drain(..).collect()
+}
+
+fn git_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(&["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let commit = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    if commit.len() > 0 {
+        Some(commit)
+    } else {
+        None
+    }
+}
+
+fn format_run(timestamp: u64, commit: &str, timings: &[Timing]) -> String {
+    timings
+        .iter()
+        .map(|t| format!("{},{},{},{},{}\n", timestamp, commit, t.day, t.first, t.second))
+        .collect::<String>()
+}
+
+pub fn append_history(path: &str, timings: &[Timing]) -> std::io::Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or(0);
+    let commit = git_commit().unwrap_or(NO_COMMIT.to_string());
+    let is_new = !std::path::Path::new(path).exists();
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if is_new {
+        writeln!(file, "{}", HISTORY_HEADER)?;
+    }
+    file.write_all(format_run(timestamp, &commit, timings).as_bytes())
+}
+
+fn parse_history(content: &str) -> Result<Vec<Run>, String> {
+    let mut runs: Vec<Run> = Vec::with_capacity(0);
+    for (i, line) in content
+        .lines()
+        .map(|l| l.trim())
+        .enumerate()
+        .filter(|(_, l)| l.len() > 0 && *l != HISTORY_HEADER)
+    {
+        let fields = line.split(",").collect::<Vec<&str>>();
+        if fields.len() != 5 {
+            return Err(format!("Line {} of history has {} fields instead of 5.", i + 1, fields.len()));
+        }
+        let parse_err = |_| format!("Could not parse line {} of history.", i + 1);
+        let timestamp = fields[0].parse::<u64>().map_err(parse_err)?;
+        let timing = Timing {
+            day: fields[2].parse::<i32>().map_err(parse_err)?,
+            first: fields[3].parse::<u128>().map_err(parse_err)?,
+            second: fields[4].parse::<u128>().map_err(parse_err)?,
+        };
+        match runs.last_mut() {
+            Some(run) if run.timestamp == timestamp && run.commit == fields[1] => {
+                run.timings.push(timing)
+            }
+            _ => runs.push(Run {
+                timestamp: timestamp,
+                commit: fields[1].to_string(),
+                timings: vec![timing],
+            }),
+        }
+    }
+    Ok(runs)
+}
+
+pub fn median(mut values: Vec<u128>) -> Option<u128> {
+    if values.len() == 0 {
+        return None;
+    }
+    values.sort();
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2
+    } else {
+        values[mid]
+    })
+}
+
+#[derive(Debug, PartialEq)]
+struct Comparison {
+    day: i32,
+    part: &'static str,
+    reference: u128,
+    latest: u128,
+    is_slowdown: bool,
+}
+
+fn is_slowdown(reference: u128, latest: u128, threshold_percent: f64) -> bool {
+    latest > reference + MIN_SLOWDOWN_MICROS
+        && latest as f64 > reference as f64 * (1.0 + threshold_percent / 100.0)
+}
+
+fn compare(latest: &Run, references: &[&Run], threshold_percent: f64) -> Vec<Comparison> {
+    type PartGetter = fn(&Timing) -> u128;
+    let parts: [(&'static str, PartGetter); 2] = [("first", |t| t.first), ("second", |t| t.second)];
+    iproduct!(latest.timings.iter(), parts.iter())
+        .filter_map(|(timing, (part, get))| {
+            let reference = median(
+                references
+                    .iter()
+                    .filter_map(|run| run.get(timing.day))
+                    .map(get)
+                    .collect(),
+            )?;
+            let latest = get(timing);
+            Some(Comparison {
+                day: timing.day,
+                part: part,
+                reference: reference,
+                latest: latest,
+                is_slowdown: is_slowdown(reference, latest, threshold_percent),
+            })
+        })
+        .collect()
+}
+
+struct PerfDiffOptions {
+    history: String,
+    baseline: Option<String>,
+    window: usize,
+    threshold_percent: f64,
+}
+
+fn parse_options(args: &[String]) -> Result<PerfDiffOptions, String> {
+    let mut options = PerfDiffOptions {
+        history: history_path(),
+        baseline: None,
+        window: 5,
+        threshold_percent: 20.0,
+    };
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        let mut value = || {
+            args_iter
+                .next()
+                .ok_or(format!("Missing value for option '{}'.", arg))
+        };
+        match arg.as_str() {
+            "--history" => options.history = value()?.clone(),
+            "--baseline" => options.baseline = Some(value()?.clone()),
+            "--window" => {
+                options.window = value()?
+                    .parse::<usize>()
+                    .map_err(|_| "Window must be a positive integer.".to_string())?
+            }
+            "--threshold" => {
+                options.threshold_percent = value()?
+                    .parse::<f64>()
+                    .map_err(|_| "Threshold must be a number in percent.".to_string())?
+            }
+            _ => return Err(format!("Unknown option '{}'.", arg)),
+        }
+    }
+    if options.window == 0 {
+        return Err("Window must be a positive integer.".to_string());
+    }
+    Ok(options)
+}
+
+/// Prints the comparison of the latest run with its reference and returns whether any
+/// part of any day has become slower than allowed.
+pub fn perf_diff(args: &[String]) -> Result<bool, String> {
+    let options = parse_options(args)?;
+    let content = fs::read_to_string(&options.history)
+        .map_err(|e| format!("Could not read history '{}': {}", options.history, e))?;
+    let runs = parse_history(&content)?;
+    let (latest, previous) = runs
+        .split_last()
+        .ok_or("History does not contain any run.".to_string())?;
+    let references = match &options.baseline {
+        Some(commit) => {
+            let baseline = previous
+                .iter()
+                .rev()
+                .find(|run| run.commit.starts_with(commit.as_str()))
+                .ok_or(format!("No run of commit '{}' in history.", commit))?;
+            println!("Comparing {} with baseline {}", latest.commit, baseline.commit);
+            vec![baseline]
+        }
+        None => {
+            let window_start = previous.len().saturating_sub(options.window);
+            let window = previous[window_start..].iter().collect::<Vec<&Run>>();
+            if window.len() == 0 {
+                return Err("History needs at least two runs for a comparison.".to_string());
+            }
+            println!(
+                "Comparing {} with the median of the {} previous runs",
+                latest.commit,
+                window.len()
+            );
+            window
+        }
+    };
+    let comparisons = compare(latest, &references, options.threshold_percent);
+    for c in &comparisons {
+        let change = if c.reference > 0 {
+            format!("{:+.1}%", (c.latest as f64 / c.reference as f64 - 1.0) * 100.0)
+        } else {
+            "n/a".to_string()
+        };
+        println!(
+            "day {:02} {:6}\t{:>10} us\t{:>10} us\t{:>8}{}",
+            c.day,
+            c.part,
+            c.reference,
+            c.latest,
+            change,
+            if c.is_slowdown { "\tSLOWER" } else { "" }
+        );
+    }
+    Ok(comparisons.iter().any(|c| c.is_slowdown))
+}
+
+#[test]
+fn test_perf() {
+    let timings = vec![
+        Timing { day: 1, first: 10, second: 20 },
+        Timing { day: 11, first: 25000, second: 54000 },
+    ];
+    let history = format!(
+        "{}\n{}{}{}",
+        HISTORY_HEADER,
+        format_run(100, "abc", &timings),
+        format_run(200, NO_COMMIT, &timings[..1]),
+        format_run(300, "def", &vec![Timing { day: 11, first: 25500, second: 70000 }]),
+    );
+    let runs = parse_history(&history).unwrap();
+    assert_eq!(runs.len(), 3);
+    assert_eq!(runs[0].timings, timings);
+    assert_eq!(runs[1].commit, NO_COMMIT);
+    assert_eq!(runs[2].get(11).unwrap().second, 70000);
+    assert!(parse_history("1,abc,1,2").is_err());
+    assert!(parse_history("1,abc,x,2,3").is_err());
+
+    assert_eq!(median(vec![]), None);
+    assert_eq!(median(vec![3, 1, 2]), Some(2));
+    assert_eq!(median(vec![4, 1, 2, 3]), Some(2));
+
+    assert!(!is_slowdown(100, 200, 20.0));
+    assert!(!is_slowdown(10000, 11000, 20.0));
+    assert!(is_slowdown(10000, 13000, 20.0));
+
+    let comparisons = compare(&runs[2], &[&runs[0], &runs[1]], 20.0);
+    assert_eq!(
+        comparisons,
+        vec![
+            Comparison { day: 11, part: "first", reference: 25000, latest: 25500, is_slowdown: false },
+            Comparison { day: 11, part: "second", reference: 54000, latest: 70000, is_slowdown: true },
+        ]
+    );
+    let options = parse_options(&["--window".to_string(), "3".to_string()]).unwrap();
+    assert_eq!(options.window, 3);
+    assert!(parse_options(&["--window".to_string()]).is_err());
+    assert!(parse_options(&["--window".to_string(), "0".to_string()]).is_err());
+    assert!(parse_options(&["--foo".to_string()]).is_err());
+
+    // two runs in quick succession are kept apart and the real history is not touched
+    let path = history_path();
+    assert_ne!(path, HISTORY_FILE);
+    let _ = fs::remove_file(&path);
+    append_history(&path, &timings).unwrap();
+    append_history(&path, &timings[..1]).unwrap();
+    let runs = parse_history(&fs::read_to_string(&path).unwrap()).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[1].timings, timings[..1].to_vec());
+    assert_eq!(parse_options(&[]).unwrap().history, path);
+}
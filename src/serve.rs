@@ -0,0 +1,341 @@
+// Minimal HTTP/1.1 server that exposes the solvers on localhost via
+//     POST /day/{n}/part/{p}
+// with the puzzle input as body. Every connection is closed after its response.
+use super::common::TaskOfDay;
+use super::solvers::find_solver;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_PORT: u16 = 8020;
+/// Puzzle inputs are a few KiB, larger bodies are rejected before they are read.
+const MAX_BODY_BYTES: usize = 1 << 20;
+/// Limits for the request line, each header line and the number of headers.
+const MAX_LINE_BYTES: usize = 8 << 10;
+const MAX_HEADERS: usize = 64;
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+/// Further connections are answered with 503 right away.
+const MAX_CONNECTIONS: usize = 16;
+
+#[derive(Debug, PartialEq)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "",
+    }
+}
+
+fn json_escape(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+fn error_response(status: u16, msg: &str) -> Response {
+    Response {
+        status: status,
+        body: format!("{{\"error\":{}}}", json_escape(msg)),
+    }
+}
+
+fn parse_route(path: &str) -> Option<(i32, usize)> {
+    let segments = path.trim_matches('/').split("/").collect::<Vec<&str>>();
+    match segments.as_slice() {
+        ["day", day, "part", part] => Some((day.parse::<i32>().ok()?, part.parse::<usize>().ok()?)),
+        _ => None,
+    }
+}
+
+fn handle_request(method: &str, path: &str, body: &str) -> Response {
+    let (day, part_number) = match parse_route(path) {
+        Some(route) => route,
+        None => return error_response(404, "Expected route /day/{n}/part/{p}."),
+    };
+    if method != "POST" {
+        return error_response(405, "Only POST is supported.");
+    }
+    let part = match part_number {
+        1 => TaskOfDay::First,
+        2 => TaskOfDay::Second,
+        _ => return error_response(404, "Part must be 1 or 2."),
+    };
+    let solver = match find_solver(day) {
+        Some(s) => s,
+        None => return error_response(404, &format!("There is no solver for day {}.", day)),
+    };
+    let now = Instant::now();
+    let res = solver.try_solve_str(body, part);
+    let elapsed = now.elapsed().as_micros();
+    let (status, answer, error) = match res {
+        Ok(Some(answer)) => (200, json_escape(&answer), "null".to_string()),
+        Ok(None) => (500, "null".to_string(), json_escape("Solver found no answer.")),
+        // solvers panic on input they cannot parse
+        Err(msg) => (400, "null".to_string(), json_escape(&msg)),
+    };
+    Response {
+        status: status,
+        body: format!(
+            "{{\"day\":{},\"part\":{},\"answer\":{},\"elapsed_micros\":{},\"error\":{}}}",
+            day, part_number, answer, elapsed, error
+        ),
+    }
+}
+
+fn io_error_response(e: io::Error) -> Response {
+    match e.kind() {
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            error_response(408, "Timed out reading the request.")
+        }
+        _ => error_response(400, &e.to_string()),
+    }
+}
+
+/// Reads a line of at most MAX_LINE_BYTES, longer lines are answered with status.
+fn read_line_limited<R: BufRead>(reader: &mut R, status: u16) -> Result<String, Response> {
+    let mut line = String::new();
+    reader
+        .by_ref()
+        .take(MAX_LINE_BYTES as u64 + 1)
+        .read_line(&mut line)
+        .map_err(io_error_response)?;
+    if line.len() > MAX_LINE_BYTES {
+        return Err(error_response(
+            status,
+            &format!("Line exceeds {} bytes.", MAX_LINE_BYTES),
+        ));
+    }
+    Ok(line)
+}
+
+fn read_request(stream: &TcpStream) -> Result<(String, String, String), Response> {
+    let bad_request = |msg: &str| error_response(400, msg);
+    let mut reader = BufReader::new(stream);
+    let request_line = read_line_limited(&mut reader, 400)?;
+    let mut request_parts = request_line.split_whitespace();
+    let method = request_parts
+        .next()
+        .ok_or_else(|| bad_request("Empty request."))?
+        .to_string();
+    let path = request_parts
+        .next()
+        .ok_or_else(|| bad_request("Missing path."))?
+        .to_string();
+    let mut content_length = 0usize;
+    for n_headers in 0.. {
+        let header = read_line_limited(&mut reader, 431)?;
+        let header = header.trim();
+        if header.len() == 0 {
+            break;
+        }
+        if n_headers == MAX_HEADERS {
+            return Err(error_response(
+                431,
+                &format!("More than {} headers.", MAX_HEADERS),
+            ));
+        }
+        if let Some((key, value)) = header.split_once(':') {
+            if key.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| bad_request("Invalid Content-Length."))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err(error_response(
+            413,
+            &format!("Body exceeds {} bytes.", MAX_BODY_BYTES),
+        ));
+    }
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).map_err(io_error_response)?;
+    let body = String::from_utf8(body).map_err(|_| bad_request("Body is not valid UTF-8."))?;
+    Ok((method, path, body))
+}
+
+fn handle_connection(stream: TcpStream) {
+    let timeouts = stream
+        .set_read_timeout(Some(IO_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(IO_TIMEOUT)));
+    if let Err(e) = timeouts {
+        eprintln!("Could not set timeouts: {}", e);
+        return;
+    }
+    match read_request(&stream) {
+        Ok((method, path, body)) => send_response(&stream, &handle_request(&method, &path, &body)),
+        Err(response) => {
+            send_response(&stream, &response);
+            // closing with unread input resets the connection, which can discard the response
+            let _ = stream.shutdown(Shutdown::Write);
+            let _ = io::copy(&mut (&stream).take(MAX_BODY_BYTES as u64), &mut io::sink());
+        }
+    }
+}
+
+fn send_response(mut stream: &TcpStream, response: &Response) {
+    let raw = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        status_text(response.status),
+        response.body.len(),
+        response.body
+    );
+    if let Err(e) = stream.write_all(raw.as_bytes()) {
+        eprintln!("Could not send response: {}", e);
+    }
+}
+
+/// Frees its place among the active connections when dropped.
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn serve_forever(listener: TcpListener, max_connections: usize) {
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                let slot = ConnectionSlot(active.clone());
+                if active.fetch_add(1, Ordering::SeqCst) >= max_connections {
+                    let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+                    send_response(&stream, &error_response(503, "Too many connections."));
+                    continue;
+                }
+                thread::spawn(move || {
+                    handle_connection(stream);
+                    drop(slot);
+                });
+            }
+            Err(e) => eprintln!("Connection failed: {}", e),
+        }
+    }
+}
+
+fn parse_port(args: &[String]) -> Result<u16, String> {
+    match args {
+        [] => Ok(DEFAULT_PORT),
+        [flag, port] if flag == "--port" => port
+            .parse::<u16>()
+            .map_err(|_| format!("Invalid port '{}'.", port)),
+        _ => Err("Usage: serve [--port N]".to_string()),
+    }
+}
+
+pub fn serve(args: &[String]) -> Result<(), String> {
+    let port = parse_port(args)?;
+    let listener = TcpListener::bind(("127.0.0.1", port)).map_err(|e| e.to_string())?;
+    println!("Listening on {}", listener.local_addr().map_err(|e| e.to_string())?);
+    serve_forever(listener, MAX_CONNECTIONS);
+    Ok(())
+}
+
+#[test]
+fn test_serve() {
+    assert_eq!(json_escape("a\"b\\c\nd\u{1}"), "\"a\\\"b\\\\c\\nd\\u0001\"");
+    assert_eq!(parse_route("/day/11/part/2"), Some((11, 2)));
+    assert_eq!(parse_route("/day/x/part/2"), None);
+    assert_eq!(parse_route("/days"), None);
+    assert_eq!(parse_port(&[]), Ok(DEFAULT_PORT));
+    assert_eq!(parse_port(&["--port".to_string(), "80".to_string()]), Ok(80));
+    assert!(parse_port(&["--port".to_string()]).is_err());
+
+    assert_eq!(handle_request("POST", "/foo", "").status, 404);
+    assert_eq!(handle_request("GET", "/day/1/part/1", "").status, 405);
+    assert_eq!(handle_request("POST", "/day/1/part/3", "").status, 404);
+    assert_eq!(handle_request("POST", "/day/15/part/1", "").status, 404);
+    // a line without the expected separators makes the solver panic
+    let res = handle_request("POST", "/day/2/part/1", "1-3 a abcde\nnonsense\n");
+    assert_eq!(res.status, 400);
+    assert!(res.body.contains("\"answer\":null"));
+
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(|| serve_forever(listener, MAX_CONNECTIONS));
+    let body = "1721\n979\n366\n299\n675\n1456\n";
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "POST /day/1/part/2 HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("\"day\":1,\"part\":2,\"answer\":\"241861950\""));
+    assert!(response.ends_with("\"error\":null}"));
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "POST /day/1/part/1 HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+        usize::MAX
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+
+    let request = |addr, raw: String| {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(raw.as_bytes()).unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    };
+    let long_path = format!("POST /{} HTTP/1.1\r\n\r\n", "x".repeat(MAX_LINE_BYTES));
+    assert!(request(addr, long_path).starts_with("HTTP/1.1 400 Bad Request\r\n"));
+    let long_header = format!(
+        "POST /day/1/part/1 HTTP/1.1\r\nX: {}\r\n\r\n",
+        "x".repeat(MAX_LINE_BYTES)
+    );
+    assert!(request(addr, long_header).starts_with("HTTP/1.1 431 "));
+    let many_headers = format!(
+        "POST /day/1/part/1 HTTP/1.1\r\n{}\r\n",
+        "X: y\r\n".repeat(MAX_HEADERS + 1)
+    );
+    assert!(request(addr, many_headers).starts_with("HTTP/1.1 431 "));
+
+    // the only slot is held by the idle connection
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(|| serve_forever(listener, 1));
+    let idle = TcpStream::connect(addr).unwrap();
+    assert!(request(addr, String::new()).starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+    drop(idle);
+}
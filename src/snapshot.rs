@@ -0,0 +1,86 @@
+// Golden snapshots of intermediate results. The snapshots live in tests/snapshots/<name>.txt.
+// Run the tests with UPDATE_SNAPSHOTS=1 to create missing snapshots or overwrite existing ones
+// with the current output. Without it, a missing snapshot fails the test.
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+const UPDATE_VAR: &str = "UPDATE_SNAPSHOTS";
+
+fn snapshot_path(name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "snapshots", &format!("{}.txt", name)]
+        .iter()
+        .collect()
+}
+
+fn is_update_requested() -> bool {
+    match env::var(UPDATE_VAR) {
+        Ok(v) => v.len() > 0 && v != "0",
+        Err(_) => false,
+    }
+}
+
+fn write_snapshot(path: &PathBuf, actual: &str) {
+    fs::create_dir_all(path.parent().unwrap()).expect("Could not create snapshot directory.");
+    fs::write(path, actual).expect("Could not write snapshot.");
+}
+
+/// Lists all lines that differ, prefixed with their line number.
+pub fn diff_lines(expected: &str, actual: &str) -> String {
+    let expected_lines = expected.lines().collect::<Vec<&str>>();
+    let actual_lines = actual.lines().collect::<Vec<&str>>();
+    let n_lines = expected_lines.len().max(actual_lines.len());
+    let mut res = String::new();
+    for i in 0..n_lines {
+        let exp = expected_lines.get(i);
+        let act = actual_lines.get(i);
+        if exp != act {
+            res.push_str(&format!(
+                "{:4} - {}\n{:4} + {}\n",
+                i + 1,
+                exp.unwrap_or(&"<missing>"),
+                i + 1,
+                act.unwrap_or(&"<missing>")
+            ));
+        }
+    }
+    res
+}
+
+pub fn assert_snapshot(name: &str, actual: &str) {
+    let path = snapshot_path(name);
+    if is_update_requested() {
+        write_snapshot(&path, actual);
+        return;
+    }
+    if !path.exists() {
+        panic!(
+            "Snapshot '{}' does not exist, rerun with {}=1 to record it.",
+            name, UPDATE_VAR
+        );
+    }
+    let expected = fs::read_to_string(&path).expect("Could not read snapshot.");
+    // git might check out the files with \r\n line endings
+    let expected = expected.replace("\r\n", "\n");
+    if expected != actual {
+        panic!(
+            "Snapshot '{}' differs, rerun with {}=1 to accept the changes.\n{}",
+            name,
+            UPDATE_VAR,
+            diff_lines(&expected, actual)
+        );
+    }
+}
+
+#[test]
+fn test_snapshot() {
+    assert_eq!(diff_lines("a\nb\nc", "a\nb\nc"), "");
+    assert_eq!(diff_lines("a\nb", "a\nx"), "   2 - b\n   2 + x\n");
+    assert_eq!(diff_lines("a", "a\nx"), "   2 - <missing>\n   2 + x\n");
+
+    if !is_update_requested() {
+        let missing = std::panic::catch_unwind(|| assert_snapshot("does_not_exist", "x"));
+        assert!(missing.is_err());
+        assert!(!snapshot_path("does_not_exist").exists());
+    }
+}
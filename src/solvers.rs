@@ -0,0 +1,102 @@
+// Registry of all solvers with type-erased answers, for callers that pick the day at runtime.
+use super::common::{string_to_lines, TaskOfDay};
+use std::panic;
+use super::{
+    day01, day02, day03, day04, day05, day06, day07, day08, day09, day10, day11, day12, day13,
+    day14, day19, day20,
+};
+
+#[derive(Clone, Copy)]
+pub struct Solver {
+    pub day: i32,
+    /// Some days separate blocks of the input by blank lines, all others ignore blank lines.
+    pub with_blank_lines: bool,
+    pub solve: fn(&Vec<String>, TaskOfDay) -> Option<String>,
+}
+
+impl Solver {
+    /// Splits a whole puzzle input into lines the same way the runner reads input files.
+    pub fn lines(&self, content: &str) -> Vec<String> {
+        let lines = string_to_lines(content);
+        if self.with_blank_lines {
+            lines
+        } else {
+            lines.into_iter().filter(|s| s.len() > 0).collect()
+        }
+    }
+
+    pub fn solve_str(&self, content: &str, part: TaskOfDay) -> Option<String> {
+        (self.solve)(&self.lines(content), part)
+    }
+
+    /// Like `solve_str` but returns the message of a panicking solver as error.
+    pub fn try_solve_str(&self, content: &str, part: TaskOfDay) -> Result<Option<String>, String> {
+        panic::catch_unwind(|| self.solve_str(content, part)).map_err(panic_message)
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "solver panicked".to_string()
+    }
+}
+
+macro_rules! solver {
+    ($day:expr, $module:ident, $with_blank_lines:expr) => {
+        Solver {
+            day: $day,
+            with_blank_lines: $with_blank_lines,
+            solve: |input, part| Some($module::run(input, part).to_string()),
+        }
+    };
+    ($day:expr, $module:ident, $with_blank_lines:expr, optional) => {
+        Solver {
+            day: $day,
+            with_blank_lines: $with_blank_lines,
+            solve: |input, part| $module::run(input, part).map(|res| res.to_string()),
+        }
+    };
+}
+
+pub fn all_solvers() -> Vec<Solver> {
+    vec![
+        solver!(1, day01, false),
+        solver!(2, day02, false),
+        solver!(3, day03, false),
+        solver!(4, day04, true),
+        solver!(5, day05, false),
+        solver!(6, day06, true),
+        solver!(7, day07, false),
+        solver!(8, day08, false),
+        solver!(9, day09, false),
+        solver!(10, day10, false, optional),
+        solver!(11, day11, false, optional),
+        solver!(12, day12, false, optional),
+        solver!(13, day13, false, optional),
+        solver!(14, day14, false, optional),
+        solver!(19, day19, true, optional),
+        solver!(20, day20, true, optional),
+    ]
+}
+
+pub fn find_solver(day: i32) -> Option<Solver> {
+    all_solvers().into_iter().find(|s| s.day == day)
+}
+
+#[test]
+fn test_solvers() {
+    assert!(find_solver(15).is_none());
+    let solver = find_solver(1).unwrap();
+    assert_eq!(solver.lines("1\n\n2\n"), vec!["1", "2"]);
+    let input = "1721\n979\n366\n299\n675\n1456\n";
+    assert_eq!(solver.solve_str(input, TaskOfDay::First), Some("514579".to_string()));
+    assert_eq!(solver.try_solve_str(input, TaskOfDay::First), Ok(Some("514579".to_string())));
+    assert!(solver.try_solve_str("x", TaskOfDay::First).is_err());
+    let solver = find_solver(6).unwrap();
+    assert_eq!(solver.lines("a\n\nb\n"), vec!["a", "", "b", ""]);
+    assert_eq!(solver.solve_str("a\nb\n\nab\n", TaskOfDay::Second), Some("2".to_string()));
+}
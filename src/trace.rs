@@ -0,0 +1,118 @@
+// Leveled debug output on stderr, such that the normal output of the runner stays clean.
+// The level is set via the environment variable AOC_LOG=error|info|debug|trace or via the
+// command line flags -v (info), -vv (debug) and -vvv (trace). Default is error.
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+pub const ENV_VAR: &str = "AOC_LOG";
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub enum Level {
+    Error = 0,
+    Info = 1,
+    Debug = 2,
+    Trace = 3,
+}
+
+static LEVEL: AtomicUsize = AtomicUsize::new(Level::Error as usize);
+
+impl Level {
+    fn from_usize(level: usize) -> Level {
+        match level {
+            0 => Level::Error,
+            1 => Level::Info,
+            2 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+    pub fn label(&self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+}
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as usize, Ordering::Relaxed);
+}
+
+pub fn is_enabled(level: Level) -> bool {
+    level as usize <= LEVEL.load(Ordering::Relaxed)
+}
+
+fn parse_level(s: &str) -> Option<Level> {
+    [Level::Error, Level::Info, Level::Debug, Level::Trace]
+        .iter()
+        .find(|l| l.label().eq_ignore_ascii_case(s.trim()))
+        .map(|l| *l)
+}
+
+/// Number of v's in flags like -v or -vvv, None for other arguments.
+fn count_verbosity(arg: &str) -> Option<usize> {
+    let vs = arg.strip_prefix("-")?;
+    if vs.len() > 0 && vs.chars().all(|c| c == 'v') {
+        Some(vs.len())
+    } else {
+        None
+    }
+}
+
+/// Sets the level from the environment and the verbosity flags and returns the remaining
+/// arguments.
+pub fn init(args: Vec<String>) -> Vec<String> {
+    let env_level = env::var(ENV_VAR).ok().and_then(|s| parse_level(&s));
+    let verbosity: usize = args.iter().filter_map(|a| count_verbosity(a)).sum();
+    let level = match env_level {
+        Some(l) if l as usize >= verbosity => l,
+        _ => Level::from_usize(verbosity),
+    };
+    set_level(level);
+    args.into_iter()
+        .filter(|a| count_verbosity(a).is_none())
+        .collect()
+}
+
+#[macro_export]
+macro_rules! trace_at {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::trace::is_enabled($level) {
+            eprintln!("[{}] {}", $level.label(), format!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => { $crate::trace_at!($crate::trace::Level::Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => { $crate::trace_at!($crate::trace::Level::Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => { $crate::trace_at!($crate::trace::Level::Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => { $crate::trace_at!($crate::trace::Level::Trace, $($arg)+) };
+}
+
+#[test]
+fn test_trace() {
+    assert_eq!(parse_level("Debug"), Some(Level::Debug));
+    assert_eq!(parse_level("verbose"), None);
+    assert_eq!(count_verbosity("-v"), Some(1));
+    assert_eq!(count_verbosity("-vvv"), Some(3));
+    assert_eq!(count_verbosity("-"), None);
+    assert_eq!(count_verbosity("--port"), None);
+    assert_eq!(count_verbosity("serve"), None);
+    assert_eq!(Level::from_usize(7), Level::Trace);
+    assert!(Level::Error < Level::Trace);
+}